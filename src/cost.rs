@@ -0,0 +1,30 @@
+//! Cost model used by cost based optimizers.
+
+use std::ops::Add;
+
+/// Cost of executing (part of) a physical plan.
+///
+/// Lower is better. The cascades optimizer compares [`Cost`] values to pick the cheapest physical
+/// alternative for a given logical group.
+#[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
+pub struct Cost(f64);
+
+impl Cost {
+    pub fn value(&self) -> f64 {
+        self.0
+    }
+}
+
+impl From<f64> for Cost {
+    fn from(cost: f64) -> Self {
+        Self(cost)
+    }
+}
+
+impl Add for Cost {
+    type Output = Cost;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Cost(self.0 + rhs.0)
+    }
+}
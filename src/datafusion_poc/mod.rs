@@ -0,0 +1,10 @@
+//! Datafusion integration: converting between this crate's [`crate::plan::Plan`] and datafusion's
+//! `LogicalPlan`/`ExecutionPlan`, and adapting this crate's rules into datafusion's optimizer rule
+//! traits.
+
+pub mod plan;
+pub mod rule;
+
+// `planner.rs` wires a `crate::cascades::CascadesOptimizer` into datafusion's `QueryPlanner`, but
+// `cascades` doesn't exist yet - it's a placeholder module declaration in `lib.rs` with nothing
+// behind it. Left undeclared here (and so out of the build) until the cascades optimizer lands.
@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::rc::Rc;
 use std::sync::Arc;
 use anyhow::bail;
@@ -8,12 +9,13 @@ use datafusion::logical_plan::JoinConstraint;
 use crate::error::OptResult;
 use crate::Expr;
 use crate::Expr::{Column as ExprColumn};
-use crate::operator::{Join, Limit, LogicalOperator, Projection, TableScan};
-use crate::operator::LogicalOperator::{LogicalJoin, LogicalLimit, LogicalProjection, LogicalScan};
+use crate::operator::{Aggregate, EmptyRelation, Filter, Join, Limit, LogicalOperator, Projection, TableScan};
+use crate::operator::LogicalOperator::{LogicalAggregate, LogicalEmptyRelation, LogicalFilter, LogicalJoin, LogicalLimit, LogicalProjection, LogicalScan};
 use crate::operator::Operator::Logical;
-use crate::plan::{PlanNode, PlanNodeIdGen};
+use crate::plan::{PlanNode, PlanNodeBuilder, PlanNodeIdGen};
+use crate::properties::LogicalProperty;
 use datafusion::logical_plan::plan::{Projection as DFProjection, Limit as DFLimit, Join as
-DFJoin, TableScan as DFTableScan, DefaultTableSource};
+DFJoin, TableScan as DFTableScan, Filter as DFFilter, Aggregate as DFAggregate, DefaultTableSource};
 use datafusion::logical_plan::{Operator as DFOperator};
 
 /// Convert data fusion logical plan to our plan.
@@ -26,42 +28,80 @@ impl<'a> TryFrom<&'a LogicalPlan> for PlanNode {
   }
 }
 
-fn df_logical_plan_to_plan_node(df_plan: &LogicalPlan, id_gen: &mut PlanNodeIdGen) ->
-OptResult<PlanNode> {
-  let id = id_gen.next();
-  let (operator, inputs) = match df_plan {
+/// The children of a datafusion logical plan node, in the same order `df_operator` expects to
+/// consume their converted counterparts.
+fn df_children(df_plan: &LogicalPlan) -> Vec<&LogicalPlan> {
+  match df_plan {
+    LogicalPlan::Projection(projection) => vec![&projection.input],
+    LogicalPlan::Limit(limit) => vec![&limit.input],
+    LogicalPlan::Join(join) => vec![&join.left, &join.right],
+    LogicalPlan::Filter(filter) => vec![&filter.input],
+    LogicalPlan::Aggregate(aggregate) => vec![&aggregate.input],
+    _ => vec![],
+  }
+}
+
+fn df_operator(df_plan: &LogicalPlan) -> OptResult<LogicalOperator> {
+  Ok(match df_plan {
     LogicalPlan::Projection(projection) => {
-      let operator = LogicalOperator::LogicalProjection(Projection::new(projection.expr.clone()));
-      let inputs = vec![df_logical_plan_to_plan_node(&projection.input, id_gen)?];
-      (operator, inputs)
-    }
-    LogicalPlan::Limit(limit) => {
-      let operator = LogicalOperator::LogicalLimit(Limit::new(limit.n));
-      let inputs = vec![df_logical_plan_to_plan_node(&limit.input, id_gen)?];
-      (operator, inputs)
+      LogicalOperator::LogicalProjection(Projection::new(projection.expr.clone()))
     }
+    LogicalPlan::Limit(limit) => LogicalOperator::LogicalLimit(Limit::new(limit.n)),
     LogicalPlan::Join(join) => {
       let join_cond = join.on.iter()
           .map(|(left, right)| ExprColumn(left.clone()).eq(ExprColumn(right.clone())))
           .reduce(|a, b| and(a, b))
           .unwrap_or(Expr::Literal(ScalarValue::Boolean(Some(true))));
-      let operator = LogicalOperator::LogicalJoin(Join::new(join.join_type, join_cond));
-      let inputs = vec![df_logical_plan_to_plan_node(&join.left, id_gen)?,
-                        df_logical_plan_to_plan_node(&join.right, id_gen)?,
-      ];
-      (operator, inputs)
+      LogicalOperator::LogicalJoin(Join::new(join.join_type, join_cond))
     }
-    LogicalPlan::TableScan(scan) => {
-      let operator = LogicalOperator::LogicalScan(TableScan::new(&scan.table_name));
-      let inputs = vec![];
-      (operator, inputs)
+    LogicalPlan::TableScan(scan) => LogicalOperator::LogicalScan(TableScan::new(&scan.table_name)),
+    LogicalPlan::Filter(filter) => LogicalOperator::LogicalFilter(Filter::new(filter.predicate.clone())),
+    LogicalPlan::Aggregate(aggregate) => {
+      LogicalOperator::LogicalAggregate(Aggregate::new(aggregate.group_expr.clone(), aggregate.aggr_expr.clone()))
+    }
+    plan => bail!("Unsupported datafusion logical plan: {:?}", plan),
+  })
+}
+
+/// Converts a datafusion logical plan to ours with an explicit work stack instead of recursion,
+/// so a long chain of projections/filters/joins can't overflow the call stack. Nodes are pushed
+/// unexpanded, then re-pushed expanded once all of their children have been converted and
+/// memoized by address, mirroring the post order traversal in [`crate::plan::Plan`].
+fn df_logical_plan_to_plan_node(df_plan: &LogicalPlan, id_gen: &mut PlanNodeIdGen) ->
+OptResult<PlanNode> {
+  let mut memo: HashMap<usize, Rc<PlanNode>> = HashMap::new();
+  let mut stack: Vec<(&LogicalPlan, bool)> = vec![(df_plan, false)];
+
+  while let Some((node, expanded)) = stack.pop() {
+    let key = node as *const LogicalPlan as usize;
+    if memo.contains_key(&key) {
+      continue;
     }
-    plan => {
-      bail!("Unsupported datafusion logical plan: {:?}", plan);
+
+    if expanded {
+      let inputs: Vec<Rc<PlanNode>> = df_children(node)
+          .into_iter()
+          .map(|child| memo[&(child as *const LogicalPlan as usize)].clone())
+          .collect();
+      let operator = Logical(df_operator(node)?);
+      let id = id_gen.next();
+      let logical_prop = Some(LogicalProperty::new(node.schema().as_ref().clone()));
+      let plan_node = PlanNodeBuilder::new(id, &operator)
+          .add_inputs(inputs)
+          .with_logical_prop(logical_prop)
+          .build();
+      memo.insert(key, Rc::new(plan_node));
+    } else {
+      stack.push((node, true));
+      for child in df_children(node) {
+        stack.push((child, false));
+      }
     }
-  };
+  }
 
-  Ok(PlanNode::new(id, Logical(operator), inputs.into_iter().map(Rc::new).collect()))
+  let root = memo.remove(&(df_plan as *const LogicalPlan as usize)).unwrap();
+  Rc::try_unwrap(root)
+      .map_err(|_| anyhow::anyhow!("internal error: root plan node was not uniquely owned"))
 }
 
 /// Converting logical plan to df plan.
@@ -93,11 +133,36 @@ fn expr_to_df_join_condition(expr: &Expr) -> OptResult<Vec<(Column, Column)>> {
   }
 }
 
+/// Converts our plan to a datafusion logical plan with an explicit work stack instead of
+/// recursion, memoizing already-converted nodes by [`PlanNodeId`] so a long chain can't overflow
+/// the call stack.
 fn plan_node_to_df_logical_plan(plan_node: &PlanNode) -> OptResult<LogicalPlan> {
-  let mut inputs = plan_node.inputs().iter()
-      .map(|p| LogicalPlan::try_from(&**p))
-      .collect::<OptResult<Vec<LogicalPlan>>>()?;
+  let mut memo: HashMap<u32, LogicalPlan> = HashMap::new();
+  let mut stack: Vec<(&PlanNode, bool)> = vec![(plan_node, false)];
+
+  while let Some((node, expanded)) = stack.pop() {
+    if memo.contains_key(&node.id()) {
+      continue;
+    }
+
+    if expanded {
+      let inputs = node.inputs().iter()
+          .map(|input| memo[&input.id()].clone())
+          .collect::<Vec<LogicalPlan>>();
+      let df_plan = build_df_logical_plan(node, inputs)?;
+      memo.insert(node.id(), df_plan);
+    } else {
+      stack.push((node, true));
+      for input in node.inputs() {
+        stack.push((&**input, false));
+      }
+    }
+  }
+
+  Ok(memo.remove(&plan_node.id()).unwrap())
+}
 
+fn build_df_logical_plan(plan_node: &PlanNode, mut inputs: Vec<LogicalPlan>) -> OptResult<LogicalPlan> {
   match plan_node.operator() {
     Logical(LogicalProjection(projection)) => {
       let df_projection = DFProjection {
@@ -145,6 +210,39 @@ fn plan_node_to_df_logical_plan(plan_node: &PlanNode) -> OptResult<LogicalPlan>
 
       Ok(LogicalPlan::TableScan(df_scan))
     },
+    Logical(LogicalFilter(filter)) => {
+      let df_filter = DFFilter {
+        predicate: filter.predicate().clone(),
+        input: Arc::new(inputs.remove(0)),
+      };
+
+      Ok(LogicalPlan::Filter(df_filter))
+    }
+    Logical(LogicalAggregate(aggregate)) => {
+      let df_aggregate = DFAggregate {
+        input: Arc::new(inputs.remove(0)),
+        group_expr: aggregate.group_by().to_vec(),
+        aggr_expr: aggregate.aggr_expr().to_vec(),
+        schema: Arc::new(plan_node.logical_prop().unwrap().schema().clone()),
+      };
+
+      Ok(LogicalPlan::Aggregate(df_aggregate))
+    }
+    Logical(LogicalEmptyRelation(EmptyRelation)) => {
+      let schema = Arc::new(plan_node.logical_prop().unwrap().schema().clone());
+      let source = Arc::new(DefaultTableSource::new(Arc::new(EmptyTable::new(Arc::new
+          ((&*schema).clone().into() )))));
+      let df_scan = DFTableScan {
+        table_name: "__empty_relation".to_string(),
+        source,
+        projection: None,
+        projected_schema: schema,
+        filters: vec![],
+        limit: Some(0),
+      };
+
+      Ok(LogicalPlan::TableScan(df_scan))
+    }
     op => bail!("Can't convert plan to data fusion logical plan: {:?}", op)
   }
 }
@@ -21,13 +21,12 @@ use async_trait::async_trait;
 /// ```
 pub struct DFQueryPlanner {
   rules: Vec<RuleImpl>,
-  optimizer_ctx: OptimizerContext,
+  optimizer_ctx: Arc<dyn OptimizerContext>,
 }
 
 #[async_trait]
 impl QueryPlanner for DFQueryPlanner {
   async fn create_physical_plan(&self, df_logical_plan: &LogicalPlan, session_state: &SessionState) -> datafusion::common::Result<Arc<dyn ExecutionPlan>> {
-    println!("Beginning to execute heuristic optimizer");
     let logical_plan = Plan::new(Arc::new(PlanNode::try_from(df_logical_plan)
         .map_err(|e| DataFusionError::Plan(format!("{:?}", e)))?
     ));
@@ -1,10 +1,11 @@
 use std::rc::Rc;
+use std::sync::Arc;
 use datafusion::common::DataFusionError;
 use datafusion::execution::context::ExecutionProps;
 use datafusion::logical_expr::LogicalPlan;
 use datafusion::optimizer::optimizer::OptimizerRule;
-use crate::heuristic::{HepOptimizer, MatchOrder};
-use crate::optimizer::{Optimizer, OptimizerContext};
+use crate::heuristic::{HepBatch, HepBatchStrategy, HepOptimizer, MatchOrder};
+use crate::optimizer::{DefaultOptimizerContext, Optimizer, OptimizerContext};
 use crate::plan::{Plan, PlanNode};
 use crate::rules::RuleImpl;
 
@@ -18,19 +19,36 @@ use crate::rules::RuleImpl;
 pub struct DFOptimizerAdapterRule {
   /// Our rules
   rules: Vec<RuleImpl>,
+  optimizer_ctx: Arc<dyn OptimizerContext>,
+}
+
+impl DFOptimizerAdapterRule {
+  pub fn new(rules: Vec<RuleImpl>) -> Self {
+    Self::with_context(rules, Arc::new(DefaultOptimizerContext::default()))
+  }
+
+  pub fn with_context(rules: Vec<RuleImpl>, optimizer_ctx: Arc<dyn OptimizerContext>) -> Self {
+    Self { rules, optimizer_ctx }
+  }
 }
 
 impl OptimizerRule for DFOptimizerAdapterRule {
   fn optimize(&self, df_plan: &LogicalPlan, _execution_props: &ExecutionProps) ->
   datafusion::common::Result<LogicalPlan> {
-    println!("Beginning to execute heuristic optimizer");
     let plan = Plan::new(Rc::new(PlanNode::try_from(df_plan)
         .map_err(|e| DataFusionError::Plan(format!("{:?}", e)))?
     ));
 
-    // Construct heuristic optimizer here
-    let hep_optimizer = HepOptimizer::new(MatchOrder::TopDown, 1000, self.rules.clone(), plan,
-                                          OptimizerContext {});
+    // Construct heuristic optimizer here: a single batch running every rule, its termination
+    // strategy and iteration limit taken from `optimizer_ctx` rather than hard-coded, so a
+    // downstream user can tune this without reconstructing the rule.
+    let strategy = if self.optimizer_ctx.fixpoint() {
+      HepBatchStrategy::FixedPoint { max_iterations: self.optimizer_ctx.max_iter_times() }
+    } else {
+      HepBatchStrategy::Once
+    };
+    let batch = HepBatch::new(self.rules.clone(), MatchOrder::TopDown, strategy);
+    let hep_optimizer = HepOptimizer::new(vec![batch], plan, self.optimizer_ctx.clone());
     let optimized_plan = hep_optimizer.find_best_plan()
         .map_err(|e| DataFusionError::Plan(format!("{:?}", e)))?;
 
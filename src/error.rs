@@ -0,0 +1,4 @@
+//! Error handling.
+
+/// Result type used throughout the optimizer.
+pub type OptResult<T> = anyhow::Result<T>;
@@ -0,0 +1,55 @@
+use crate::heuristic::MatchOrder;
+use crate::rules::RuleImpl;
+
+/// How many passes a [`HepBatch`] makes over the plan before giving up.
+#[derive(Clone, Debug)]
+pub enum HepBatchStrategy {
+    /// Run every rule in the batch against every matched node exactly once.
+    Once,
+    /// Repeat the batch, re-scanning the whole plan each time, until a full pass changes nothing
+    /// or `max_iterations` is reached.
+    FixedPoint { max_iterations: usize },
+}
+
+impl HepBatchStrategy {
+    fn max_iterations(&self) -> usize {
+        match self {
+            HepBatchStrategy::Once => 1,
+            HepBatchStrategy::FixedPoint { max_iterations } => *max_iterations,
+        }
+    }
+}
+
+/// A group of rules applied together with their own match order and termination strategy.
+///
+/// [`super::HepOptimizer::find_best_plan`] runs batches in order, so callers can stage rewrites
+/// (e.g. push filters down to a fixed point, then prune projections once) instead of throwing
+/// every rule at every node on every pass.
+#[derive(Clone, Debug)]
+pub struct HepBatch {
+    rules: Vec<RuleImpl>,
+    match_order: MatchOrder,
+    strategy: HepBatchStrategy,
+}
+
+impl HepBatch {
+    pub fn new(rules: Vec<RuleImpl>, match_order: MatchOrder, strategy: HepBatchStrategy) -> Self {
+        Self {
+            rules,
+            match_order,
+            strategy,
+        }
+    }
+
+    pub fn rules(&self) -> &[RuleImpl] {
+        &self.rules
+    }
+
+    pub fn match_order(&self) -> MatchOrder {
+        self.match_order
+    }
+
+    pub fn max_iterations(&self) -> usize {
+        self.strategy.max_iterations()
+    }
+}
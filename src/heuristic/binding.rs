@@ -0,0 +1,84 @@
+//! Matches a [`Pattern`] against a plan graph, the way calcite's `HepRuleCall` binds a rule's
+//! operand tree to a candidate subtree before invoking the rule.
+
+use crate::optimizer::{OptExpr, Optimizer};
+use crate::rules::{OptExprNode, OptExpression, Pattern};
+
+pub(super) struct Binding<'a, O: Optimizer> {
+    root: O::ExprHandle,
+    pattern: &'a Pattern,
+    optimizer: &'a O,
+    done: bool,
+}
+
+impl<'a, O: Optimizer> Binding<'a, O> {
+    pub(super) fn new(root: O::ExprHandle, pattern: &'a Pattern, optimizer: &'a O) -> Self {
+        Self {
+            root,
+            pattern,
+            optimizer,
+            done: false,
+        }
+    }
+}
+
+impl<'a, O: Optimizer> Iterator for Binding<'a, O> {
+    type Item = OptExpression<O>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        self.done = true;
+        bind(self.root, self.pattern, self.optimizer)
+    }
+}
+
+/// Recursively matches `pattern` against the expression at `handle`. A leaf child pattern checks
+/// its predicate against the input and binds that input by handle rather than descending further;
+/// a root pattern with no children binds the root as an operator and each of its inputs by handle.
+/// An interior pattern requires an exact arity match with its children's patterns.
+fn bind<O: Optimizer>(handle: O::ExprHandle, pattern: &Pattern, optimizer: &O) -> Option<OptExpression<O>> {
+    let expr = optimizer.expr_at(handle);
+    if !(pattern.predict)(expr.operator()) {
+        return None;
+    }
+
+    let inputs = match &pattern.children {
+        None => (0..expr.inputs_len(optimizer))
+            .map(|idx| {
+                let input_handle = expr.input_at(idx, optimizer);
+                OptExpression::new(OptExprNode::ExprHandleNode(input_handle), vec![])
+                    .with_logical_prop(optimizer.expr_at(input_handle).logical_prop().cloned())
+            })
+            .collect(),
+        Some(children) => {
+            if children.len() != expr.inputs_len(optimizer) {
+                return None;
+            }
+            children
+                .iter()
+                .enumerate()
+                .map(|(idx, child_pattern)| {
+                    let input_handle = expr.input_at(idx, optimizer);
+                    if child_pattern.children.is_some() {
+                        return bind(input_handle, child_pattern, optimizer);
+                    }
+                    let input = optimizer.expr_at(input_handle);
+                    if !(child_pattern.predict)(input.operator()) {
+                        return None;
+                    }
+                    Some(
+                        OptExpression::new(OptExprNode::ExprHandleNode(input_handle), vec![])
+                            .with_logical_prop(input.logical_prop().cloned()),
+                    )
+                })
+                .collect::<Option<Vec<_>>>()?
+        }
+    };
+
+    Some(
+        OptExpression::new(OptExprNode::OperatorNode(expr.operator().clone()), inputs)
+            .with_logical_prop(expr.logical_prop().cloned()),
+    )
+}
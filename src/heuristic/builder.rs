@@ -0,0 +1,111 @@
+//! A builder that drives a caller-supplied set of [`OptimizerRule`]s over a [`Plan`] to fixpoint.
+
+use std::rc::Rc;
+
+use crate::error::OptResult;
+use crate::heuristic::rule::OptimizerRule;
+use crate::optimizer::{DefaultOptimizerContext, OptimizerContext};
+use crate::plan::Plan;
+
+/// Builds a heuristic optimization run out of an ordered list of [`OptimizerRule`]s.
+///
+/// Rules run in registration order on every pass; a pass that rewrites nothing means the plan
+/// has reached a fixed point and the run stops early. When the context's
+/// [`OptimizerContext::fixpoint`] is `false`, only a single pass runs.
+pub struct HepOptimizerBuilder {
+    max_iter_times: usize,
+    fixpoint: bool,
+    plan: Plan,
+    context: Box<dyn OptimizerContext>,
+    rules: Vec<Box<dyn OptimizerRule>>,
+}
+
+impl HepOptimizerBuilder {
+    pub fn new(plan: Plan) -> Self {
+        Self::with_context(plan, Box::new(DefaultOptimizerContext::default()))
+    }
+
+    pub fn with_context(plan: Plan, context: Box<dyn OptimizerContext>) -> Self {
+        Self {
+            max_iter_times: context.max_iter_times(),
+            fixpoint: context.fixpoint(),
+            plan,
+            context,
+            rules: vec![],
+        }
+    }
+
+    pub fn max_iter_times(mut self, max_iter_times: usize) -> Self {
+        self.max_iter_times = max_iter_times;
+        self
+    }
+
+    pub fn context(mut self, context: Box<dyn OptimizerContext>) -> Self {
+        self.max_iter_times = context.max_iter_times();
+        self.fixpoint = context.fixpoint();
+        self.context = context;
+        self
+    }
+
+    /// Registers a rule. Rules are tried in the order they were added.
+    pub fn add_rule(mut self, rule: Box<dyn OptimizerRule>) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    pub fn add_rules<I>(mut self, rules: I) -> Self
+    where
+        I: IntoIterator<Item = Box<dyn OptimizerRule>>,
+    {
+        self.rules.extend(rules);
+        self
+    }
+
+    /// Runs the registered rules over the plan.
+    ///
+    /// For a rule that supports the owned-rewrite fast path, this first checks whether `root` is
+    /// uniquely owned (via `Rc::try_unwrap`) and, if so, hands the rule the node by value instead
+    /// of rebuilding a rewritten subtree around a borrowed `Rc`. If the node is shared - or the
+    /// rule doesn't support the fast path - it falls back to `try_optimize` as before.
+    ///
+    /// Makes at most `max_iter_times` passes, stopping early once a pass rewrites nothing; when the
+    /// context's [`OptimizerContext::fixpoint`] is `false` it makes a single pass. When the
+    /// context's [`OptimizerContext::disabled`] is `true` it runs no rules and returns the plan as is.
+    pub fn optimize(self) -> OptResult<Plan> {
+        if self.context.disabled() {
+            return Ok(self.plan);
+        }
+        let ctx = self.context.as_ref();
+        let mut root = self.plan.root();
+        let passes = if self.fixpoint { self.max_iter_times } else { 1 };
+        for _ in 0..passes {
+            let mut changed = false;
+            for rule in &self.rules {
+                if rule.supports_owned() {
+                    root = match Rc::try_unwrap(root) {
+                        Ok(owned) => {
+                            let (owned, rule_changed) = rule.try_optimize_owned(owned, ctx)?;
+                            changed |= rule_changed;
+                            Rc::new(owned)
+                        }
+                        Err(shared) => {
+                            if let Some(new_root) = rule.try_optimize(&shared, ctx)? {
+                                changed = true;
+                                new_root
+                            } else {
+                                shared
+                            }
+                        }
+                    };
+                } else if let Some(new_root) = rule.try_optimize(&root, ctx)? {
+                    root = new_root;
+                    changed = true;
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+        Ok(Plan::new(root))
+    }
+}
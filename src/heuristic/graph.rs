@@ -3,21 +3,33 @@ use std::collections::HashMap;
 use std::rc::Rc;
 
 use petgraph::prelude::{NodeIndex, StableGraph};
-use petgraph::visit::Bfs;
+use petgraph::visit::Topo;
 use petgraph::{Directed, Direction};
 
 use crate::heuristic::{HepOptimizer, MatchOrder};
-use crate::operator::Operator;
-use crate::optimizer::{OptExpr, OptExprHandle, OptGroup, OptGroupHandle};
+use crate::operator::{LogicalOperator, Operator};
+use crate::optimizer::{OptExpr, OptExprHandle, OptGroup, OptGroupHandle, OptimizerContext};
 use crate::plan::{Plan, PlanNode, PlanNodeBuilder, PlanNodeId, PlanNodeRef};
-use crate::properties::{LogicalProperty, PhysicalPropertySet};
+use crate::properties::{derive_logical_prop, LogicalProperty, PhysicalPropertySet};
 use crate::rules::OptExprNode::{ExprHandleNode, GroupHandleNode, OperatorNode};
 use crate::rules::OptExpression;
-use crate::stat::Statistics;
+use crate::stat::{derive_statistics, Statistics};
 
 type HepGraph = StableGraph<HepOptimizerNode, (), Directed, PlanNodeId>;
 pub type HepNodeId = NodeIndex<PlanNodeId>;
 
+/// Whether `operator` must be excluded from the `(Operator, inputs)` dedup map.
+///
+/// The map assumes an operator's own fields fully determine a node's meaning, so two nodes with
+/// the same operator and inputs are interchangeable. `EmptyRelation` breaks that: it's a unit
+/// struct whose schema lives entirely on the surrounding node's `logical_prop`, so two
+/// schema-distinct empty relations (e.g. ones synthesized by `EliminateLimitRule` and
+/// `PropagateEmptyRelationRule` for different subtrees) would otherwise collapse onto the same
+/// key and the second would silently inherit the first's schema.
+fn dedup_exempt(operator: &Operator) -> bool {
+    matches!(operator, Operator::Logical(LogicalOperator::LogicalEmptyRelation(_)))
+}
+
 pub struct HepOptimizerNode {
     id: HepNodeId,
     operator: Operator,
@@ -30,6 +42,10 @@ pub struct HepOptimizerNode {
 pub(super) struct PlanGraph {
     pub(super) graph: HepGraph,
     root: HepNodeId,
+    /// Structurally identical subtrees - same operator, same child node ids - map to a single
+    /// node, so a plan where one subexpression feeds two parents (a self-join, a shared
+    /// CTE-like subplan) isn't duplicated and re-optimized independently.
+    dedup: HashMap<(Operator, Vec<HepNodeId>), HepNodeId>,
 }
 
 impl PlanGraph {
@@ -52,10 +68,12 @@ impl PlanGraph {
         &mut self,
         opt_node: OptExpression<HepOptimizer>,
         origin_node_id: HepNodeId,
+        context: &dyn OptimizerContext,
     ) -> bool {
-        let new_hep_node_id = self.insert_opt_node(&opt_node);
+        let new_hep_node_id = self.insert_opt_node(&opt_node, context);
         if new_hep_node_id != origin_node_id {
-            // Redirect parents's child to new node
+            // Redirect parents's child to new node. Every parent is redirected before the old node
+            // is removed below, so a node shared by several parents leaves none of them dangling.
             let parent_node_ids: Vec<HepNodeId> = self
                 .graph
                 .neighbors_directed(origin_node_id, Direction::Incoming)
@@ -63,6 +81,23 @@ impl PlanGraph {
             for parent in parent_node_ids {
                 self.graph.add_edge(parent, new_hep_node_id, ());
             }
+
+            // The node being replaced is gone; drop its dedup entry so a future structurally
+            // identical insert doesn't resolve to a now-removed `HepNodeId`. The dedup map is
+            // keyed with inputs in forward (insertion) order, but `StableGraph` hands back a
+            // node's edges in reverse insertion order, so the neighbor list has to be reversed
+            // before it matches the key `insert_opt_node`/`from_plan` originally stored.
+            let origin_node = &self.graph[origin_node_id];
+            let mut origin_inputs: Vec<HepNodeId> = self
+                .graph
+                .neighbors_directed(origin_node_id, Direction::Outgoing)
+                .collect();
+            origin_inputs.reverse();
+            let origin_key = (origin_node.operator.clone(), origin_inputs);
+            if self.dedup.get(&origin_key) == Some(&origin_node_id) {
+                self.dedup.remove(&origin_key);
+            }
+
             self.graph.remove_node(origin_node_id);
 
             if self.root == origin_node_id {
@@ -75,67 +110,113 @@ impl PlanGraph {
         }
     }
 
-    fn insert_opt_node(&mut self, opt_expr: &OptExpression<HepOptimizer>) -> HepNodeId {
-        match opt_expr.node() {
-            ExprHandleNode(expr_handle) => *expr_handle,
-            GroupHandleNode(group_handle) => *group_handle,
-            OperatorNode(operator) => {
-                let input_hep_node_ids: Vec<HepNodeId> = opt_expr
-                    .inputs()
-                    .iter()
-                    .map(|input_expr| self.insert_opt_node(&*input_expr))
-                    .collect();
-
-                let hep_node = HepOptimizerNode {
-                    // Currently this id is fake.
-                    id: HepNodeId::default(),
-                    operator: operator.clone(),
-                    logical_prop: None,
-                    stat: None,
-                    physical_props: None,
-                };
-
-                let new_node_id = self.graph.add_node(hep_node);
-                // reset node id
-                self.graph[new_node_id].id = new_node_id;
-                for input_hep_node_id in input_hep_node_ids {
-                    self.graph.add_edge(new_node_id, input_hep_node_id, ());
-                }
+    /// Inserts a rule's replacement subtree into the graph with an explicit work stack instead of
+    /// recursion, so a deep rewrite chain can't overflow the call stack. Nodes are keyed by
+    /// address for memoization: a node is pushed unexpanded, then re-pushed expanded once all of
+    /// its inputs have been inserted, mirroring `PostOrderPlanNodeIter`.
+    fn insert_opt_node(&mut self, opt_expr: &OptExpression<HepOptimizer>, context: &dyn OptimizerContext) -> HepNodeId {
+        let mut memo: HashMap<usize, HepNodeId> = HashMap::new();
+        let mut stack: Vec<(&OptExpression<HepOptimizer>, bool)> = vec![(opt_expr, false)];
+
+        while let Some((expr, expanded)) = stack.pop() {
+            let key = expr as *const OptExpression<HepOptimizer> as usize;
+            if memo.contains_key(&key) {
+                continue;
+            }
 
-                // TODO: Derive logical prop, stats here
-                new_node_id
+            match expr.node() {
+                ExprHandleNode(expr_handle) => {
+                    memo.insert(key, *expr_handle);
+                }
+                GroupHandleNode(group_handle) => {
+                    memo.insert(key, *group_handle);
+                }
+                OperatorNode(operator) => {
+                    if expanded {
+                        let input_hep_node_ids: Vec<HepNodeId> = expr
+                            .inputs()
+                            .iter()
+                            .map(|input_expr| {
+                                memo[&(input_expr as *const OptExpression<HepOptimizer> as usize)]
+                            })
+                            .collect();
+
+                        let dedup_key = (operator.clone(), input_hep_node_ids.clone());
+                        let dedup_hit = if dedup_exempt(operator) { None } else { self.dedup.get(&dedup_key) };
+                        let node_id = if let Some(existing_id) = dedup_hit {
+                            *existing_id
+                        } else {
+                            let input_stats: Vec<Option<Statistics>> = input_hep_node_ids
+                                .iter()
+                                .map(|input_hep_node_id| self.graph[*input_hep_node_id].stat.clone())
+                                .collect();
+                            let input_props: Vec<Option<LogicalProperty>> = input_hep_node_ids
+                                .iter()
+                                .map(|input_hep_node_id| self.graph[*input_hep_node_id].logical_prop.clone())
+                                .collect();
+                            let hep_node = HepOptimizerNode {
+                                // Currently this id is fake.
+                                id: HepNodeId::default(),
+                                operator: operator.clone(),
+                                logical_prop: expr
+                                    .logical_prop()
+                                    .cloned()
+                                    .or_else(|| derive_logical_prop(operator, &input_props)),
+                                stat: derive_statistics(operator, &input_stats, context),
+                                physical_props: None,
+                            };
+
+                            let new_node_id = self.graph.add_node(hep_node);
+                            // reset node id
+                            self.graph[new_node_id].id = new_node_id;
+                            for input_hep_node_id in &input_hep_node_ids {
+                                self.graph.add_edge(new_node_id, *input_hep_node_id, ());
+                            }
+
+                            // `EmptyRelation` is a unit struct: its schema lives entirely on the
+                            // node's `logical_prop`, not on the operator itself, so two
+                            // schema-distinct empties would otherwise collapse onto the same
+                            // `(Operator, inputs)` dedup key. Leave it out of the map entirely
+                            // rather than have a later one silently inherit an earlier schema.
+                            if !dedup_exempt(operator) {
+                                self.dedup.insert(dedup_key, new_node_id);
+                            }
+                            new_node_id
+                        };
+                        memo.insert(key, node_id);
+                    } else {
+                        stack.push((expr, true));
+                        for input in expr.inputs() {
+                            stack.push((input, false));
+                        }
+                    }
+                }
             }
         }
+
+        memo[&(opt_expr as *const OptExpression<HepOptimizer> as usize)]
     }
 
-    /// Return node ids in bottom up order.
+    /// Return node ids in bottom up order: every node comes after all of its children, including
+    /// a node shared by several parents, which a plain reversed breadth-first walk isn't
+    /// guaranteed to get right once the graph is a genuine DAG rather than a tree.
     fn bottom_up_node_iters(&self) -> impl Iterator<Item = HepNodeId> {
-        let mut ids = Vec::with_capacity(self.graph.node_count());
-        let mut bfs = Bfs::new(&self.graph, self.root);
-
-        // Create plan node for each `HepOptimizerNode`
-        while let Some(node_id) = bfs.next(&self.graph) {
-            ids.push(node_id);
-        }
-
-        ids.into_iter().rev()
+        self.top_down_node_iters().collect::<Vec<_>>().into_iter().rev()
     }
 
-    /// Return node ids in bottom up order.
+    /// Return node ids in top down order: every node comes before all of its children.
     fn top_down_node_iters(&self) -> impl Iterator<Item = HepNodeId> {
         let mut ids = Vec::with_capacity(self.graph.node_count());
-        let mut bfs = Bfs::new(&self.graph, self.root);
+        let mut topo = Topo::new(&self.graph);
 
-        // Create plan node for each `HepOptimizerNode`
-        while let Some(node_id) = bfs.next(&self.graph) {
+        while let Some(node_id) = topo.next(&self.graph) {
             ids.push(node_id);
         }
 
         ids.into_iter()
     }
 
-    pub(super) fn to_plan(&self) -> Plan {
-        let next_plan_node_id = 1u32;
+    pub(super) fn to_plan(&self, context: &dyn OptimizerContext) -> Plan {
         let mut hep_node_id_to_plan_node = HashMap::<HepNodeId, PlanNodeRef>::new();
         // Traverse nodes in bottom up order, when visiting a node, its children all inserted
         // into map
@@ -147,7 +228,7 @@ impl PlanGraph {
                 .map(|node_id| hep_node_id_to_plan_node.get(&node_id).unwrap().clone())
                 .collect();
 
-            let plan_node = PlanNodeBuilder::new(next_plan_node_id, &node.operator)
+            let plan_node = PlanNodeBuilder::new(context.next_plan_node_id(), &node.operator)
                 .with_statistics(node.stat.clone())
                 .with_logical_prop(node.logical_prop.clone())
                 .with_physical_props(node.physical_props.clone())
@@ -163,40 +244,62 @@ impl PlanGraph {
     }
 }
 
-/// Converts from raw plan to plan graph.
-impl From<Plan> for PlanGraph {
-    fn from(plan: Plan) -> Self {
+impl PlanGraph {
+    /// Converts from raw plan to plan graph, deriving statistics and logical properties for any
+    /// node that didn't already carry its own (consulting `context`'s catalog for table scans).
+    pub(super) fn from_plan(plan: Plan, context: &dyn OptimizerContext) -> Self {
         let mut graph = HepGraph::default();
-        let mut parents = HashMap::<PlanNodeId, Vec<PlanNodeId>>::new();
         let mut node_id_map = HashMap::<PlanNodeId, HepNodeId>::new();
+        let mut dedup = HashMap::<(Operator, Vec<HepNodeId>), HepNodeId>::new();
+
+        // Post order, so a node's children are already in `node_id_map` by the time it's visited
+        // and can be deduped against: a plan built as a tree (e.g. a self-join scanning the same
+        // table twice) collapses into the DAG the optimizer actually reasons about.
+        for plan_node_ref in plan.post_order_iterator() {
+            let input_hep_node_ids: Vec<HepNodeId> = plan_node_ref
+                .inputs()
+                .iter()
+                .map(|input| *node_id_map.get(&input.id()).unwrap())
+                .collect();
 
-        for plan_node_ref in plan.bfs_iterator() {
-            for input in plan_node_ref.inputs() {
-                parents
-                    .entry(plan_node_ref.id())
-                    .or_insert_with(Vec::new)
-                    .push(input.id());
-            }
-            let plan_node = (&*plan_node_ref).into();
-            let plan_node_id = graph.add_node(plan_node);
-            graph[plan_node_id].id = plan_node_id;
-            node_id_map.insert(plan_node_ref.id(), plan_node_id);
-        }
+            let dedup_key = (plan_node_ref.operator().clone(), input_hep_node_ids.clone());
+            let dedup_hit = if dedup_exempt(plan_node_ref.operator()) { None } else { dedup.get(&dedup_key) };
+            let hep_node_id = if let Some(existing_id) = dedup_hit {
+                *existing_id
+            } else {
+                let mut hep_node: HepOptimizerNode = (&*plan_node_ref).into();
+                if hep_node.stat.is_none() {
+                    let input_stats: Vec<Option<Statistics>> = input_hep_node_ids
+                        .iter()
+                        .map(|input_hep_node_id| graph[*input_hep_node_id].stat.clone())
+                        .collect();
+                    hep_node.stat = derive_statistics(&hep_node.operator, &input_stats, context);
+                }
+                if hep_node.logical_prop.is_none() {
+                    let input_props: Vec<Option<LogicalProperty>> = input_hep_node_ids
+                        .iter()
+                        .map(|input_hep_node_id| graph[*input_hep_node_id].logical_prop.clone())
+                        .collect();
+                    hep_node.logical_prop = derive_logical_prop(&hep_node.operator, &input_props);
+                }
+                let new_node_id = graph.add_node(hep_node);
+                graph[new_node_id].id = new_node_id;
+                for input_hep_node_id in &input_hep_node_ids {
+                    graph.add_edge(new_node_id, *input_hep_node_id, ());
+                }
+                // See the matching comment in `insert_opt_node`: `EmptyRelation`'s schema lives
+                // only on `logical_prop`, so it's excluded from dedup entirely.
+                if !dedup_exempt(plan_node_ref.operator()) {
+                    dedup.insert(dedup_key, new_node_id);
+                }
+                new_node_id
+            };
 
-        for (node_id, inputs) in parents {
-            for input_id in inputs {
-                graph.add_edge(
-                    *node_id_map.get(&node_id).unwrap(),
-                    *node_id_map.get(&input_id).unwrap(),
-                    (),
-                );
-            }
+            node_id_map.insert(plan_node_ref.id(), hep_node_id);
         }
 
-        Self {
-            graph,
-            root: *node_id_map.get(&(&*plan.root()).id()).unwrap(),
-        }
+        let root = *node_id_map.get(&(&*plan.root()).id()).unwrap();
+        Self { graph, root, dedup }
     }
 }
 
@@ -236,6 +339,18 @@ impl OptExpr for HepOptimizerNode {
             .nth(idx)
             .unwrap()
     }
+
+    fn logical_prop(&self) -> Option<&LogicalProperty> {
+        self.logical_prop.as_ref()
+    }
+
+    fn stat(&self) -> Option<&Statistics> {
+        self.stat.as_ref()
+    }
+
+    fn physical_props(&self) -> Option<&PhysicalPropertySet> {
+        self.physical_props.as_ref()
+    }
 }
 
 impl OptExprHandle for HepNodeId {
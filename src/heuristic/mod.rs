@@ -10,3 +10,9 @@ pub use optimizer::*;
 mod graph;
 pub use graph::*;
 mod binding;
+mod batch;
+pub use batch::*;
+mod rule;
+pub use rule::*;
+mod builder;
+pub use builder::*;
@@ -1,36 +1,29 @@
-
-
-
-
-use anyhow::{ensure};
-
-
-
+use std::sync::Arc;
 
 use crate::error::OptResult;
+use crate::heuristic::batch::HepBatch;
 use crate::heuristic::binding::Binding;
 use crate::heuristic::graph::{HepOptimizerNode, PlanGraph};
 use crate::heuristic::HepNodeId;
 use crate::optimizer::{OptExpr, Optimizer, OptimizerContext};
 use crate::plan::Plan;
 
-use crate::rules::{Rule, RuleImpl, RuleResult};
+use crate::rules::{Rule, RuleImpl};
 
 
 /// Match order of plan tree.
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug)]
 pub enum MatchOrder {
     BottomUp,
     TopDown,
 }
 
 pub struct HepOptimizer {
-    match_order: MatchOrder,
-    /// Max number of iteration
-    max_iter_times: usize,
-    rules: Vec<RuleImpl>,
+    /// Batches run in order; each drives the per-node/per-rule loop with its own match order and
+    /// termination strategy.
+    batches: Vec<HepBatch>,
     pub(super) graph: PlanGraph,
-    context: OptimizerContext,
+    context: Arc<dyn OptimizerContext>,
 }
 
 impl Optimizer for HepOptimizer {
@@ -39,8 +32,8 @@ impl Optimizer for HepOptimizer {
     type Group = HepOptimizerNode;
     type GroupHandle = HepNodeId;
 
-    fn context(&self) -> &OptimizerContext {
-        &self.context
+    fn context(&self) -> &dyn OptimizerContext {
+        self.context.as_ref()
     }
 
     fn group_at(&self, group_handle: HepNodeId) -> &HepOptimizerNode {
@@ -52,63 +45,58 @@ impl Optimizer for HepOptimizer {
     }
 
     fn find_best_plan(mut self) -> OptResult<Plan> {
-        for _times in 0..self.max_iter_times {
-            // The plan no longer changes after iteration
-            let mut fixed_point = true;
-            let node_ids = self.graph.nodes_iter(self.match_order.clone());
-            for node_id in node_ids {
-                let expr_handle = node_id;
-
-                for rule in &*self.rules.clone() {
-                    println!(
-                        "Trying to apply rule {:?} to expression {:?}",
-                        rule,
-                        self.expr_at(expr_handle).operator()
-                    );
-                    if self.apply_rule(rule.clone(), expr_handle.clone())? {
-                        println!(
-                            "Plan after applying rule {:?} is {:?}",
-                            rule,
-                            self.graph.to_plan()
-                        );
-                        fixed_point = false;
-                        break;
-                    } else {
-                        println!(
-                            "Skipped applying rule {:?} to expression {:?}",
-                            rule,
-                            self.expr_at(expr_handle).operator()
-                        );
+        if self.context.disabled() {
+            // Pass-through: hand back the input plan unchanged, e.g. to isolate whether a bug was
+            // already present in the unoptimized plan or was introduced by a rule.
+            return Ok(self.graph.to_plan(self.context.as_ref()));
+        }
+
+        for batch in self.batches.clone() {
+            for _times in 0..batch.max_iterations() {
+                // The plan no longer changes after iteration
+                let mut fixed_point = true;
+                // Collected once up front, then matched against while rewrites mutate the graph
+                // underneath this loop. That's only safe because `replace_opt_expression` removes
+                // exactly the node it was asked to replace (`origin_node_id`) and nothing else -
+                // a rewritten child is left in the graph, orphaned but still present, until some
+                // later iteration matches and replaces it in turn. A rule root is also never
+                // revisited later in `node_ids` for this same pass (each id appears once), so the
+                // removed id is never dereferenced again. If a future rule ever removed more than
+                // its matched root (e.g. pruning a now-unreachable subtree eagerly), a later
+                // `node_id` in this list could index a freed `HepNodeId` in `expr_at`/`group_at` -
+                // re-derive the frontier per rewrite instead of relying on this invariant.
+                let node_ids = self.graph.nodes_iter(batch.match_order());
+                for node_id in node_ids {
+                    let expr_handle = node_id;
+
+                    for rule in batch.rules() {
+                        if !self.context.is_rule_enabled(&rule.name()) {
+                            continue;
+                        }
+
+                        if self.apply_rule(rule.clone(), expr_handle)? {
+                            fixed_point = false;
+                            break;
+                        }
                     }
                 }
 
-                if !fixed_point {
+                if fixed_point {
                     break;
                 }
             }
-
-            if fixed_point {
-                break;
-            }
         }
 
-        Ok(self.graph.to_plan())
+        Ok(self.graph.to_plan(self.context.as_ref()))
     }
 }
 
 impl HepOptimizer {
-    pub fn new(
-        match_order: MatchOrder,
-        max_iter_times: usize,
-        rules: Vec<RuleImpl>,
-        plan: Plan,
-        context: OptimizerContext,
-    ) -> Self {
+    pub fn new(batches: Vec<HepBatch>, plan: Plan, context: Arc<dyn OptimizerContext>) -> Self {
+        let graph = PlanGraph::from_plan(plan, context.as_ref());
         Self {
-            match_order,
-            max_iter_times,
-            rules,
-            graph: PlanGraph::from(plan),
+            batches,
+            graph,
             context,
         }
     }
@@ -116,21 +104,15 @@ impl HepOptimizer {
     fn apply_rule(&mut self, rule: RuleImpl, expr_handle: HepNodeId) -> OptResult<bool> {
         let original_hep_node_id = expr_handle;
         if let Some(opt_node) = Binding::new(expr_handle, &*rule.pattern(), self).next() {
-            let mut results = RuleResult::new();
-            rule.apply(opt_node, self, &mut results)?;
-
-            for (idx, new_expr) in results.results().enumerate() {
-                ensure!(
-                    idx < 1,
-                    "Rewrite rule should not return no more than 1 result."
-                );
-                return Ok(self
-                    .graph
-                    .replace_opt_expression(new_expr, original_hep_node_id));
+            match rule.apply(opt_node, self)? {
+                Some(new_expr) => Ok(self.graph.replace_opt_expression(
+                    new_expr,
+                    original_hep_node_id,
+                    self.context.as_ref(),
+                )),
+                // No transformation generated.
+                None => Ok(false),
             }
-
-            // No transformation generated.
-            return Ok(false);
         } else {
             Ok(false)
         }
@@ -0,0 +1,51 @@
+//! Public extension point for downstream crates that want to register their own heuristic
+//! rewrites without forking [`crate::rules`].
+
+use crate::error::OptResult;
+use crate::optimizer::OptimizerContext;
+use crate::plan::{PlanNode, PlanNodeRef};
+
+/// A user-defined rewrite rule applied to a whole [`crate::plan::Plan`] by
+/// [`super::HepOptimizerBuilder::optimize`].
+///
+/// Unlike the pattern-matched [`crate::rules::Rule`]s used internally by [`super::HepOptimizer`],
+/// an `OptimizerRule` is handed a whole subtree and is free to walk it however it likes. This
+/// mirrors datafusion's `OptimizerRule`, so callers already familiar with that API can plug their
+/// rewrites straight into this crate's heuristic optimizer.
+///
+/// Following the datafusion convention, `try_optimize` returns `Ok(None)` to mean "no change" and
+/// `Ok(Some(new_node))` to mean "here is the rewritten subtree", so the driver can tell fixpoint
+/// apart from a no-op rewrite without comparing whole plans structurally.
+pub trait OptimizerRule {
+    /// Tries to rewrite `node`, returning `Ok(None)` if the rule does not apply.
+    fn try_optimize(&self, node: &PlanNodeRef, ctx: &dyn OptimizerContext) -> OptResult<Option<PlanNodeRef>>;
+
+    /// A short, unique name identifying this rule.
+    fn name(&self) -> &str;
+
+    /// Whether this rule implements the owned-rewrite fast path below. Defaults to `false`, in
+    /// which case the driver never calls `try_optimize_owned` and always goes through
+    /// `try_optimize` instead.
+    fn supports_owned(&self) -> bool {
+        false
+    }
+
+    /// Like `try_optimize`, but takes `node` by value instead of by shared reference.
+    ///
+    /// [`super::HepOptimizerBuilder::optimize`] only takes this path when it has just proven, via
+    /// `Rc::try_unwrap`, that it holds the sole reference to `node` - so an owned-aware rule can
+    /// swap `node`'s inputs (via [`PlanNode::inputs_mut`]) or otherwise rebuild it in place,
+    /// instead of paying for a fresh [`PlanNodeBuilder`] and [`std::rc::Rc`] allocation for every
+    /// unchanged sibling on the way back up. Because the caller no longer has a borrowed copy of
+    /// `node` to fall back on the way `try_optimize`'s callers do, this hands `node` back
+    /// unconditionally, paired with whether it actually changed, rather than using the
+    /// `Option`-as-fixpoint-signal convention used elsewhere in this crate.
+    fn try_optimize_owned(
+        &self,
+        node: PlanNode,
+        ctx: &dyn OptimizerContext,
+    ) -> OptResult<(PlanNode, bool)> {
+        let _ = ctx;
+        Ok((node, false))
+    }
+}
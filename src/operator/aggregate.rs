@@ -0,0 +1,141 @@
+use crate::cost::Cost;
+use crate::error::OptResult;
+use crate::operator::{DerivePropContext, DerivePropResult, PhysicalOperatorTrait};
+use crate::optimizer::{OptExpr, Optimizer};
+use crate::properties::{OrderProp, PhysicalProp, PhysicalPropertySet};
+use crate::stat::Statistics;
+use crate::Expr;
+
+/// Logical aggregation: groups rows by `group_by` and computes `aggr_expr` (e.g. `SUM(x)`,
+/// `COUNT(*)`) over each group.
+#[derive(Clone, Debug, Hash, Eq, PartialEq)]
+pub struct Aggregate {
+    group_by: Vec<Expr>,
+    aggr_expr: Vec<Expr>,
+}
+
+impl Aggregate {
+    pub fn new(group_by: Vec<Expr>, aggr_expr: Vec<Expr>) -> Self {
+        Self { group_by, aggr_expr }
+    }
+
+    pub fn group_by(&self) -> &[Expr] {
+        &self.group_by
+    }
+
+    pub fn aggr_expr(&self) -> &[Expr] {
+        &self.aggr_expr
+    }
+}
+
+/// The plain column names among `exprs`, e.g. an aggregate's group-by columns that are simple
+/// column references rather than computed expressions (`date_trunc(...)`, etc.) - those are the
+/// only ones whose distinct counts or existing ordering this crate can reason about.
+pub fn column_names(exprs: &[Expr]) -> Vec<String> {
+    exprs
+        .iter()
+        .filter_map(|expr| match expr {
+            Expr::Column(c) => Some(c.name.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+fn input_row_count<O: Optimizer>(expr_handle: O::ExprHandle, optimizer: &O) -> f64 {
+    let input_handle = optimizer.expr_at(expr_handle).input_at(0, optimizer);
+    optimizer
+        .expr_at(input_handle)
+        .stat()
+        .map(Statistics::row_count)
+        .unwrap_or(0) as f64
+}
+
+/// Hash aggregate: builds an in-memory hash table keyed by the group-by columns, accumulating
+/// each group's aggregates as rows are consumed. Needs no particular input order and produces
+/// none.
+#[derive(Clone, Debug, Hash, Eq, PartialEq)]
+pub struct HashAggregate {
+    group_by: Vec<Expr>,
+    aggr_expr: Vec<Expr>,
+}
+
+impl HashAggregate {
+    pub fn new(group_by: Vec<Expr>, aggr_expr: Vec<Expr>) -> Self {
+        Self { group_by, aggr_expr }
+    }
+
+    pub fn group_by(&self) -> &[Expr] {
+        &self.group_by
+    }
+
+    pub fn aggr_expr(&self) -> &[Expr] {
+        &self.aggr_expr
+    }
+}
+
+impl PhysicalOperatorTrait for HashAggregate {
+    fn derive_properties<O: Optimizer>(
+        &self,
+        _context: DerivePropContext<O>,
+    ) -> OptResult<Vec<DerivePropResult>> {
+        Ok(vec![DerivePropResult {
+            output_prop: PhysicalPropertySet::default(),
+            input_required_props: vec![PhysicalPropertySet::default()],
+        }])
+    }
+
+    fn cost<O: Optimizer>(&self, expr_handle: O::ExprHandle, optimizer: &O) -> OptResult<Cost> {
+        Ok(Cost::from(input_row_count(expr_handle, optimizer)))
+    }
+}
+
+/// Sort aggregate: relies on its input already being ordered on the group-by columns to compute
+/// each group's aggregates with a single streaming pass, and preserves that ordering on output.
+/// Costs the same as [`HashAggregate`] (one pass over its input) when the input is already sorted;
+/// more otherwise, since it then has to pay for sorting.
+#[derive(Clone, Debug, Hash, Eq, PartialEq)]
+pub struct SortAggregate {
+    group_by: Vec<Expr>,
+    aggr_expr: Vec<Expr>,
+}
+
+impl SortAggregate {
+    pub fn new(group_by: Vec<Expr>, aggr_expr: Vec<Expr>) -> Self {
+        Self { group_by, aggr_expr }
+    }
+
+    pub fn group_by(&self) -> &[Expr] {
+        &self.group_by
+    }
+
+    pub fn aggr_expr(&self) -> &[Expr] {
+        &self.aggr_expr
+    }
+}
+
+impl PhysicalOperatorTrait for SortAggregate {
+    fn derive_properties<O: Optimizer>(
+        &self,
+        _context: DerivePropContext<O>,
+    ) -> OptResult<Vec<DerivePropResult>> {
+        let order = OrderProp::new(column_names(&self.group_by));
+        Ok(vec![DerivePropResult {
+            output_prop: PhysicalPropertySet::new(Some(order.clone()), None),
+            input_required_props: vec![PhysicalPropertySet::new(Some(order), None)],
+        }])
+    }
+
+    fn cost<O: Optimizer>(&self, expr_handle: O::ExprHandle, optimizer: &O) -> OptResult<Cost> {
+        let rows = input_row_count(expr_handle, optimizer);
+        let required_order = OrderProp::new(column_names(&self.group_by));
+        let input_handle = optimizer.expr_at(expr_handle).input_at(0, optimizer);
+        let already_sorted = optimizer
+            .expr_at(input_handle)
+            .physical_props()
+            .and_then(|props| props.order())
+            .map_or(false, |order| order.satisfies(&required_order));
+
+        let cost = if already_sorted || rows <= 1.0 { rows } else { rows * rows.log2() };
+        Ok(Cost::from(cost))
+    }
+}
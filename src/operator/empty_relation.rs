@@ -0,0 +1,6 @@
+/// A relation statically known to produce zero rows.
+///
+/// Like `TableScan`/`Join`, it doesn't carry its own schema — that lives on the surrounding
+/// `PlanNode`'s `logical_prop`, derived from whatever subtree it replaced.
+#[derive(Clone, Debug, Default, Hash, Eq, PartialEq)]
+pub struct EmptyRelation;
@@ -0,0 +1,16 @@
+use datafusion::logical_plan::Expr;
+
+#[derive(Clone, Debug, Hash, Eq, PartialEq)]
+pub struct Filter {
+    predicate: Expr,
+}
+
+impl Filter {
+    pub fn new(predicate: Expr) -> Self {
+        Self { predicate }
+    }
+
+    pub fn predicate(&self) -> &Expr {
+        &self.predicate
+    }
+}
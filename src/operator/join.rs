@@ -1,12 +1,23 @@
+use std::collections::HashSet;
+
+use datafusion::common::Column;
+use datafusion::logical_plan::{DFSchema, Operator as DFOperator};
 use datafusion::prelude::JoinType;
 
 use crate::cost::Cost;
 use crate::error::OptResult;
 use crate::operator::{DerivePropContext, DerivePropResult, PhysicalOperatorTrait};
-use crate::optimizer::Optimizer;
-use crate::properties::PhysicalPropertySet;
+use crate::optimizer::{OptExpr, Optimizer, OptimizerContext};
+use crate::properties::{DistributionProp, OrderProp, PhysicalProp, PhysicalPropertySet};
+use crate::stat::Statistics;
 use crate::Expr;
 
+/// Fraction of a shuffle join's required hash-partition columns that must already be present in a
+/// side's current partitioning for that side to be treated as already correctly partitioned (and
+/// so left alone rather than reshuffled), when the `broadcast_leniency_ratio` setting isn't
+/// present on the [`crate::optimizer::OptimizerContext`] in use.
+const DEFAULT_PARTITION_REUSE_LENIENCY: f64 = 1.0;
+
 /// Logical join operator.
 #[derive(Clone, Debug, Hash, Eq, PartialEq)]
 pub struct Join {
@@ -28,21 +39,330 @@ impl Join {
     }
 }
 
-impl PhysicalOperatorTrait for Join {
+/// Splits a join condition into its equi-join conjuncts, e.g. `l.a = r.b AND l.c = r.d` becomes
+/// `[(l.a, r.b), (l.c, r.d)]`. A conjunct that isn't a plain column-to-column equality (a residual
+/// predicate like `l.a < r.b`, or anything this doesn't recognize) is dropped, since neither
+/// `HashJoin` nor `SortMergeJoin` can use it to key their inputs; the caller falls back to
+/// `NestedLoopJoin` when nothing is left.
+pub(crate) fn equi_join_columns(expr: &Expr) -> Vec<(Column, Column)> {
+    match expr {
+        Expr::BinaryExpr { left, op, right } if matches!(op, DFOperator::And) => {
+            let mut pairs = equi_join_columns(left);
+            pairs.extend(equi_join_columns(right));
+            pairs
+        }
+        Expr::BinaryExpr { left, op, right } if matches!(op, DFOperator::Eq) => {
+            match (left.as_ref(), right.as_ref()) {
+                (Expr::Column(l), Expr::Column(r)) => vec![(l.clone(), r.clone())],
+                _ => vec![],
+            }
+        }
+        _ => vec![],
+    }
+}
+
+/// Sorts equi-join column pairs into the ones coming from the left input and the ones coming from
+/// the right input, using each input's schema.
+pub(crate) fn split_equi_join_columns(
+    equi_keys: &[(Column, Column)],
+    left_schema: &DFSchema,
+    right_schema: &DFSchema,
+) -> (Vec<String>, Vec<String>) {
+    let mut left_cols = vec![];
+    let mut right_cols = vec![];
+    for (a, b) in equi_keys {
+        for column in [a, b] {
+            if left_schema.index_of_column(column).is_ok() {
+                left_cols.push(column.name.clone());
+            } else if right_schema.index_of_column(column).is_ok() {
+                right_cols.push(column.name.clone());
+            }
+        }
+    }
+    (left_cols, right_cols)
+}
+
+/// Sorts `expr`'s equi-join columns into the ones coming from the left input and the ones coming
+/// from the right input, using each input's already-derived schema. Returns `None` if either
+/// input's schema hasn't been derived yet.
+fn join_key_columns<O: Optimizer>(
+    context: &DerivePropContext<O>,
+    expr: &Expr,
+) -> Option<(Vec<String>, Vec<String>)> {
+    let join_node = context.optimizer.expr_at(context.expr_handle);
+    let left_handle = join_node.input_at(0, context.optimizer);
+    let right_handle = join_node.input_at(1, context.optimizer);
+    let left_schema = context.optimizer.expr_at(left_handle).logical_prop()?.schema();
+    let right_schema = context.optimizer.expr_at(right_handle).logical_prop()?.schema();
+
+    Some(split_equi_join_columns(&equi_join_columns(expr), left_schema, right_schema))
+}
+
+/// Row count of a join's `idx`-th input, or `0` if it hasn't been derived yet - treating an
+/// unknown input as free rather than making every join look prohibitively expensive.
+fn input_row_count<O: Optimizer>(context_optimizer: &O, expr_handle: O::ExprHandle, idx: usize) -> f64 {
+    let input_handle = context_optimizer.expr_at(expr_handle).input_at(idx, context_optimizer);
+    context_optimizer
+        .expr_at(input_handle)
+        .stat()
+        .map(Statistics::row_count)
+        .unwrap_or(0) as f64
+}
+
+/// Estimated byte size of a join's two inputs, or `usize::MAX` for one whose statistics haven't
+/// been derived yet - treating an unknown input as too big to broadcast rather than the opposite.
+fn input_byte_sizes<O: Optimizer>(context: &DerivePropContext<O>) -> (usize, usize) {
+    let join_node = context.optimizer.expr_at(context.expr_handle);
+    let byte_size = |idx: usize| {
+        let input_handle = join_node.input_at(idx, context.optimizer);
+        context
+            .optimizer
+            .expr_at(input_handle)
+            .stat()
+            .map(Statistics::byte_size)
+            .unwrap_or(usize::MAX)
+    };
+    (byte_size(0), byte_size(1))
+}
+
+/// Whether an input already providing `existing` distribution can stand in for a requirement of
+/// `HashPartitioned(required_cols)` without reshuffling, i.e. at least `leniency` of the required
+/// columns are already among its partitioning columns.
+fn already_hash_partitioned(existing: Option<&DistributionProp>, required_cols: &[String], leniency: f64) -> bool {
+    if required_cols.is_empty() {
+        return false;
+    }
+    match existing {
+        Some(DistributionProp::HashPartitioned(existing_cols)) => {
+            let existing_cols: HashSet<&String> = existing_cols.iter().collect();
+            let matched = required_cols.iter().filter(|c| existing_cols.contains(c)).count();
+            (matched as f64 / required_cols.len() as f64) >= leniency
+        }
+        _ => false,
+    }
+}
+
+/// Builds the shuffle (hash-repartition both sides on their join columns) alternative, reusing an
+/// input's current partitioning instead of requiring a fresh reshuffle where `leniency` allows it.
+fn shuffle_alternative<O: Optimizer>(
+    context: &DerivePropContext<O>,
+    left_cols: Vec<String>,
+    right_cols: Vec<String>,
+    leniency: f64,
+) -> DerivePropResult {
+    let join_node = context.optimizer.expr_at(context.expr_handle);
+    let left_existing = context
+        .optimizer
+        .expr_at(join_node.input_at(0, context.optimizer))
+        .physical_props()
+        .and_then(PhysicalPropertySet::distribution);
+    let right_existing = context
+        .optimizer
+        .expr_at(join_node.input_at(1, context.optimizer))
+        .physical_props()
+        .and_then(PhysicalPropertySet::distribution);
+
+    let required_distribution = |existing: Option<&DistributionProp>, cols: Vec<String>| {
+        if already_hash_partitioned(existing, &cols, leniency) {
+            DistributionProp::Any
+        } else {
+            DistributionProp::HashPartitioned(cols)
+        }
+    };
+
+    DerivePropResult {
+        // Neither side's ordering is preserved by a hash shuffle.
+        output_prop: PhysicalPropertySet::default(),
+        input_required_props: vec![
+            PhysicalPropertySet::new(None, Some(required_distribution(left_existing, left_cols))),
+            PhysicalPropertySet::new(None, Some(required_distribution(right_existing, right_cols))),
+        ],
+    }
+}
+
+/// Builds the broadcast alternative: replicate whichever side is estimated smaller to every
+/// partition, leave the other side's partitioning as-is, and preserve that larger side's
+/// distribution on output.
+fn broadcast_alternative<O: Optimizer>(context: &DerivePropContext<O>, left_bytes: usize, right_bytes: usize) -> DerivePropResult {
+    let join_node = context.optimizer.expr_at(context.expr_handle);
+    let left_handle = join_node.input_at(0, context.optimizer);
+    let right_handle = join_node.input_at(1, context.optimizer);
+
+    let (left_req, right_req, preserved_side_handle) = if left_bytes <= right_bytes {
+        (DistributionProp::Broadcast, DistributionProp::Any, right_handle)
+    } else {
+        (DistributionProp::Any, DistributionProp::Broadcast, left_handle)
+    };
+    let preserved_distribution = context
+        .optimizer
+        .expr_at(preserved_side_handle)
+        .physical_props()
+        .and_then(PhysicalPropertySet::distribution)
+        .cloned();
+
+    DerivePropResult {
+        output_prop: PhysicalPropertySet::new(None, preserved_distribution),
+        input_required_props: vec![
+            PhysicalPropertySet::new(None, Some(left_req)),
+            PhysicalPropertySet::new(None, Some(right_req)),
+        ],
+    }
+}
+
+/// Hash join: builds an in-memory hash table keyed by the right input's join columns, then probes
+/// it once per row of the left input. Cheapest of the three when both inputs can be hash
+/// partitioned on their join columns, but only applicable to equi-joins.
+#[derive(Clone, Debug, Hash, Eq, PartialEq)]
+pub struct HashJoin {
+    join_type: JoinType,
+    expr: Expr,
+}
+
+impl HashJoin {
+    pub fn new(join_type: JoinType, expr: Expr) -> Self {
+        Self { join_type, expr }
+    }
+
+    pub fn join_type(&self) -> JoinType {
+        self.join_type
+    }
+
+    pub fn expr(&self) -> &Expr {
+        &self.expr
+    }
+}
+
+impl PhysicalOperatorTrait for HashJoin {
     fn derive_properties<O: Optimizer>(
         &self,
-        _context: DerivePropContext<O>,
+        context: DerivePropContext<O>,
+    ) -> OptResult<Vec<DerivePropResult>> {
+        let (left_cols, right_cols) = join_key_columns(&context, &self.expr).unwrap_or_default();
+        let opt_context = context.optimizer.context();
+        let broadcast_threshold = opt_context.broadcast_threshold();
+        let leniency = opt_context
+            .setting("broadcast_leniency_ratio")
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_PARTITION_REUSE_LENIENCY);
+
+        let mut alternatives = vec![shuffle_alternative(&context, left_cols, right_cols, leniency)];
+
+        let (left_bytes, right_bytes) = input_byte_sizes(&context);
+        if left_bytes.min(right_bytes) <= broadcast_threshold {
+            alternatives.push(broadcast_alternative(&context, left_bytes, right_bytes));
+        }
+
+        Ok(alternatives)
+    }
+
+    fn cost<O: Optimizer>(&self, expr_handle: O::ExprHandle, optimizer: &O) -> OptResult<Cost> {
+        // One pass building the hash table over the right input, one pass probing it with the
+        // left input.
+        let left_rows = input_row_count(optimizer, expr_handle, 0);
+        let right_rows = input_row_count(optimizer, expr_handle, 1);
+        Ok(Cost::from(left_rows + right_rows))
+    }
+}
+
+/// Sort-merge join: sorts both inputs on their join columns, then merges them in a single pass.
+/// Useful when an input is already sorted on its join columns (e.g. fed by an index scan), since
+/// that side's sort is then free: `cost` prices a side whose `physical_props` order already
+/// covers its join columns at a single pass, and every other side at a full sort.
+#[derive(Clone, Debug, Hash, Eq, PartialEq)]
+pub struct SortMergeJoin {
+    join_type: JoinType,
+    expr: Expr,
+}
+
+impl SortMergeJoin {
+    pub fn new(join_type: JoinType, expr: Expr) -> Self {
+        Self { join_type, expr }
+    }
+
+    pub fn join_type(&self) -> JoinType {
+        self.join_type
+    }
+
+    pub fn expr(&self) -> &Expr {
+        &self.expr
+    }
+}
+
+impl PhysicalOperatorTrait for SortMergeJoin {
+    fn derive_properties<O: Optimizer>(
+        &self,
+        context: DerivePropContext<O>,
     ) -> OptResult<Vec<DerivePropResult>> {
+        let (left_cols, right_cols) = join_key_columns(&context, &self.expr).unwrap_or_default();
         Ok(vec![DerivePropResult {
-            output_prop: PhysicalPropertySet::default(),
+            // The merge step emits rows in left-join-column order.
+            output_prop: PhysicalPropertySet::new(Some(OrderProp::new(left_cols.clone())), None),
             input_required_props: vec![
-                PhysicalPropertySet::default(),
-                PhysicalPropertySet::default(),
+                PhysicalPropertySet::new(
+                    Some(OrderProp::new(left_cols.clone())),
+                    Some(DistributionProp::HashPartitioned(left_cols)),
+                ),
+                PhysicalPropertySet::new(
+                    Some(OrderProp::new(right_cols.clone())),
+                    Some(DistributionProp::HashPartitioned(right_cols)),
+                ),
             ],
         }])
     }
 
-    fn cost<O: Optimizer>(&self, _expr_handle: O::ExprHandle, _optimizer: &O) -> OptResult<Cost> {
-        Ok(Cost::from(1.0))
+    fn cost<O: Optimizer>(&self, expr_handle: O::ExprHandle, optimizer: &O) -> OptResult<Cost> {
+        let left_rows = input_row_count(optimizer, expr_handle, 0);
+        let right_rows = input_row_count(optimizer, expr_handle, 1);
+        let (left_cols, right_cols) =
+            join_key_columns(&DerivePropContext { optimizer, expr_handle }, &self.expr).unwrap_or_default();
+        let already_sorted = |idx: usize, cols: Vec<String>| {
+            let input_handle = optimizer.expr_at(expr_handle).input_at(idx, optimizer);
+            let order = optimizer.expr_at(input_handle).physical_props().and_then(PhysicalPropertySet::order);
+            !cols.is_empty() && order.map_or(false, |order| order.satisfies(&OrderProp::new(cols)))
+        };
+        let sort_cost = |rows: f64, sorted: bool| if sorted || rows <= 1.0 { rows } else { rows * rows.log2() };
+        let left_cost = sort_cost(left_rows, already_sorted(0, left_cols));
+        Ok(Cost::from(left_cost + sort_cost(right_rows, already_sorted(1, right_cols))))
+    }
+}
+
+/// Nested loop join: for every row of the left input, scans the whole right input looking for
+/// matches. Requires nothing of either input's order or distribution, so it's the only one of the
+/// three that can evaluate a non-equi condition - at the cost of quadratic row scans.
+#[derive(Clone, Debug, Hash, Eq, PartialEq)]
+pub struct NestedLoopJoin {
+    join_type: JoinType,
+    expr: Expr,
+}
+
+impl NestedLoopJoin {
+    pub fn new(join_type: JoinType, expr: Expr) -> Self {
+        Self { join_type, expr }
+    }
+
+    pub fn join_type(&self) -> JoinType {
+        self.join_type
+    }
+
+    pub fn expr(&self) -> &Expr {
+        &self.expr
+    }
+}
+
+impl PhysicalOperatorTrait for NestedLoopJoin {
+    fn derive_properties<O: Optimizer>(
+        &self,
+        _context: DerivePropContext<O>,
+    ) -> OptResult<Vec<DerivePropResult>> {
+        Ok(vec![DerivePropResult {
+            output_prop: PhysicalPropertySet::default(),
+            input_required_props: vec![PhysicalPropertySet::default(), PhysicalPropertySet::default()],
+        }])
+    }
+
+    fn cost<O: Optimizer>(&self, expr_handle: O::ExprHandle, optimizer: &O) -> OptResult<Cost> {
+        let left_rows = input_row_count(optimizer, expr_handle, 0);
+        let right_rows = input_row_count(optimizer, expr_handle, 1);
+        Ok(Cost::from(left_rows * right_rows))
     }
 }
@@ -1,7 +1,7 @@
 
 use enum_as_inner::EnumAsInner;
 
-use crate::operator::{Join, Limit, Projection, TableScan};
+use crate::operator::{Aggregate, EmptyRelation, Filter, Join, Limit, Projection, TableScan};
 
 /// Logical relational operator.
 #[derive(Clone, Debug, Hash, Eq, PartialEq, EnumAsInner)]
@@ -10,4 +10,7 @@ pub enum LogicalOperator {
     LogicalProjection(Projection),
     LogicalJoin(Join),
     LogicalScan(TableScan),
+    LogicalFilter(Filter),
+    LogicalEmptyRelation(EmptyRelation),
+    LogicalAggregate(Aggregate),
 }
@@ -0,0 +1,72 @@
+//! Relational operators, both logical and physical.
+
+mod aggregate;
+pub use aggregate::*;
+mod empty_relation;
+pub use empty_relation::*;
+mod filter;
+pub use filter::*;
+mod join;
+pub use join::*;
+mod limit;
+pub use limit::*;
+mod logical;
+pub use logical::*;
+mod projection;
+pub use projection::*;
+mod table_scan;
+pub use table_scan::*;
+
+use enum_as_inner::EnumAsInner;
+
+use crate::cost::Cost;
+use crate::error::OptResult;
+use crate::optimizer::Optimizer;
+use crate::properties::PhysicalPropertySet;
+
+/// A relational expression, either logical or physical.
+#[derive(Clone, Debug, Hash, Eq, PartialEq, EnumAsInner)]
+pub enum Operator {
+    Logical(LogicalOperator),
+    Physical(PhysicalOperator),
+}
+
+/// Physical relational operator.
+#[derive(Clone, Debug, Hash, Eq, PartialEq, EnumAsInner)]
+pub enum PhysicalOperator {
+    PhysicalTableScan(TableScan),
+    PhysicalHashJoin(HashJoin),
+    PhysicalSortMergeJoin(SortMergeJoin),
+    PhysicalNestedLoopJoin(NestedLoopJoin),
+    PhysicalHashAggregate(HashAggregate),
+    PhysicalSortAggregate(SortAggregate),
+}
+
+/// Context passed to [`PhysicalOperatorTrait::derive_properties`], giving a physical operator
+/// access to its own handle in the optimizer currently deriving its properties.
+pub struct DerivePropContext<'a, O: Optimizer> {
+    pub optimizer: &'a O,
+    pub expr_handle: O::ExprHandle,
+}
+
+/// One way a physical operator can satisfy a required output property, and what it in turn
+/// requires from each of its inputs to do so.
+pub struct DerivePropResult {
+    pub output_prop: PhysicalPropertySet,
+    pub input_required_props: Vec<PhysicalPropertySet>,
+}
+
+/// Behavior every physical operator must implement so the cost based optimizer can enumerate
+/// physical alternatives and pick the cheapest one.
+pub trait PhysicalOperatorTrait {
+    /// Enumerates the ways this operator can satisfy required properties, along with what each
+    /// way requires from its children.
+    fn derive_properties<O: Optimizer>(
+        &self,
+        context: DerivePropContext<O>,
+    ) -> OptResult<Vec<DerivePropResult>>;
+
+    /// Estimates the cost of executing this operator, given its inputs have already been
+    /// optimized.
+    fn cost<O: Optimizer>(&self, expr_handle: O::ExprHandle, optimizer: &O) -> OptResult<Cost>;
+}
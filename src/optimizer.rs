@@ -0,0 +1,217 @@
+//! The [`Optimizer`] abstraction shared by the heuristic and cascades optimizers.
+//!
+//! Rules are written against this trait rather than against a concrete optimizer so the same
+//! [`crate::rules::Rule`] can run unchanged under either search strategy.
+
+use std::cell::Cell;
+use std::collections::{HashMap, HashSet};
+
+use crate::error::OptResult;
+use crate::operator::Operator;
+use crate::plan::{Plan, PlanNodeId};
+use crate::properties::{LogicalProperty, PhysicalPropertySet};
+use crate::stat::Statistics;
+
+/// Byte size below which a join input is considered small enough to broadcast rather than
+/// shuffle, absent a more specific [`OptimizerContext::broadcast_threshold`].
+const DEFAULT_BROADCAST_THRESHOLD_BYTES: usize = 10 * 1024 * 1024;
+
+/// Shared configuration and id-minting state threaded through rule invocation.
+///
+/// Both [`crate::heuristic::HepOptimizer`] and the cascades optimizer carry one of these so rules
+/// don't need to reach for ad hoc globals to mint node ids or look up tuning knobs. It's a trait,
+/// rather than a concrete struct, so downstream crates can plug in their own implementation (e.g.
+/// backed by a request-scoped id allocator or a config service) while still satisfying
+/// [`Optimizer::context`]'s `&dyn OptimizerContext` return type.
+pub trait OptimizerContext {
+    /// Mints a fresh [`PlanNodeId`], unique within this context, for a rule to assign to a
+    /// replacement node.
+    fn next_plan_node_id(&self) -> PlanNodeId;
+
+    /// Maximum number of passes a heuristic batch will make over the plan before giving up, even
+    /// if it hasn't reached a fixed point.
+    fn max_iter_times(&self) -> usize;
+
+    /// Whether a heuristic batch should keep iterating until a fixed point (no rule fires on a
+    /// whole pass) rather than stopping after a single pass regardless.
+    fn fixpoint(&self) -> bool;
+
+    /// A per-rule tuning knob, keyed by whatever name the rule and its caller have agreed on.
+    fn setting(&self, key: &str) -> Option<&str>;
+
+    /// Whether the rule named `rule_name` (see [`crate::rules::Rule::name`]) is allowed to run.
+    /// Lets a caller disable individual rules - e.g. while bisecting a miscompile - without
+    /// reconstructing the whole rule list. Defaults to allowing every rule.
+    fn is_rule_enabled(&self, _rule_name: &str) -> bool {
+        true
+    }
+
+    /// Byte size below which a join input is considered small enough to broadcast (replicate to
+    /// every partition) rather than shuffle.
+    fn broadcast_threshold(&self) -> usize {
+        DEFAULT_BROADCAST_THRESHOLD_BYTES
+    }
+
+    /// Precomputed statistics for a named table, e.g. sourced from a catalog. Consulted in place
+    /// of guesswork when a `TableScan` has no `LIMIT` of its own to estimate from.
+    fn table_stats(&self, _table_name: &str) -> Option<Statistics> {
+        None
+    }
+
+    /// When `true`, the optimizer runs no rules at all and hands back the input plan unchanged -
+    /// useful for isolating whether a bug was already present in the unoptimized plan or was
+    /// introduced by a rule.
+    fn disabled(&self) -> bool {
+        false
+    }
+}
+
+/// The default, in-memory [`OptimizerContext`], backed by a settings map.
+#[derive(Debug)]
+pub struct DefaultOptimizerContext {
+    next_plan_node_id: Cell<PlanNodeId>,
+    max_iter_times: usize,
+    fixpoint: bool,
+    settings: HashMap<String, String>,
+    /// `None` means every rule is enabled; `Some(names)` restricts to just those names.
+    enabled_rules: Option<HashSet<String>>,
+    broadcast_threshold: usize,
+    table_stats: HashMap<String, Statistics>,
+    disabled: bool,
+}
+
+impl DefaultOptimizerContext {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_max_iter_times(mut self, max_iter_times: usize) -> Self {
+        self.max_iter_times = max_iter_times;
+        self
+    }
+
+    pub fn with_fixpoint(mut self, fixpoint: bool) -> Self {
+        self.fixpoint = fixpoint;
+        self
+    }
+
+    pub fn with_setting<K: Into<String>, V: Into<String>>(mut self, key: K, value: V) -> Self {
+        self.settings.insert(key.into(), value.into());
+        self
+    }
+
+    pub fn with_enabled_rules<I: IntoIterator<Item = String>>(mut self, rule_names: I) -> Self {
+        self.enabled_rules = Some(rule_names.into_iter().collect());
+        self
+    }
+
+    pub fn with_broadcast_threshold(mut self, broadcast_threshold: usize) -> Self {
+        self.broadcast_threshold = broadcast_threshold;
+        self
+    }
+
+    pub fn with_table_stats<S: Into<String>>(mut self, table_name: S, stats: Statistics) -> Self {
+        self.table_stats.insert(table_name.into(), stats);
+        self
+    }
+
+    pub fn with_disabled(mut self, disabled: bool) -> Self {
+        self.disabled = disabled;
+        self
+    }
+}
+
+impl Default for DefaultOptimizerContext {
+    fn default() -> Self {
+        Self {
+            next_plan_node_id: Cell::new(0),
+            max_iter_times: 1000,
+            fixpoint: true,
+            settings: HashMap::new(),
+            enabled_rules: None,
+            broadcast_threshold: DEFAULT_BROADCAST_THRESHOLD_BYTES,
+            table_stats: HashMap::new(),
+            disabled: false,
+        }
+    }
+}
+
+impl OptimizerContext for DefaultOptimizerContext {
+    fn next_plan_node_id(&self) -> PlanNodeId {
+        let id = self.next_plan_node_id.get();
+        self.next_plan_node_id.set(id + 1);
+        id
+    }
+
+    fn max_iter_times(&self) -> usize {
+        self.max_iter_times
+    }
+
+    fn fixpoint(&self) -> bool {
+        self.fixpoint
+    }
+
+    fn setting(&self, key: &str) -> Option<&str> {
+        self.settings.get(key).map(String::as_str)
+    }
+
+    fn is_rule_enabled(&self, rule_name: &str) -> bool {
+        self.enabled_rules.as_ref().map_or(true, |enabled| enabled.contains(rule_name))
+    }
+
+    fn broadcast_threshold(&self) -> usize {
+        self.broadcast_threshold
+    }
+
+    fn table_stats(&self, table_name: &str) -> Option<Statistics> {
+        self.table_stats.get(table_name).cloned()
+    }
+
+    fn disabled(&self) -> bool {
+        self.disabled
+    }
+}
+
+/// Drives some search strategy (heuristic rewriting, cascades-style enumeration) over a graph of
+/// relational expressions.
+pub trait Optimizer: Sized {
+    type Expr: OptExpr<O = Self, InputHandle = Self::ExprHandle>;
+    type ExprHandle: OptExprHandle<O = Self> + Copy;
+    type Group: OptGroup;
+    type GroupHandle: OptGroupHandle<O = Self> + Copy;
+
+    fn context(&self) -> &dyn OptimizerContext;
+    fn group_at(&self, group_handle: Self::GroupHandle) -> &Self::Group;
+    fn expr_at(&self, expr_handle: Self::ExprHandle) -> &Self::Expr;
+    fn find_best_plan(self) -> OptResult<Plan>;
+}
+
+/// A relational expression as seen by an [`Optimizer`] — just enough to match patterns against
+/// it: its operator and its children.
+pub trait OptExpr {
+    type InputHandle;
+    type O: Optimizer;
+
+    fn operator(&self) -> &Operator;
+    fn inputs_len(&self, opt: &Self::O) -> usize;
+    fn input_at(&self, idx: usize, opt: &Self::O) -> Self::InputHandle;
+    /// The derived schema/output properties of this expression, if they have been computed yet.
+    fn logical_prop(&self) -> Option<&LogicalProperty>;
+    /// The derived statistics of this expression, if they have been computed yet.
+    fn stat(&self) -> Option<&Statistics>;
+    /// The physical properties (order, distribution) this expression provides, if it's a physical
+    /// expression whose properties have been derived yet.
+    fn physical_props(&self) -> Option<&PhysicalPropertySet>;
+}
+
+pub trait OptExprHandle {
+    type O: Optimizer;
+}
+
+/// A group of equivalent relational expressions, as seen by an [`Optimizer`]. The heuristic
+/// optimizer's "groups" are single expressions; the cascades optimizer's groups hold alternatives.
+pub trait OptGroup {}
+
+pub trait OptGroupHandle {
+    type O: Optimizer;
+}
@@ -6,8 +6,8 @@ use datafusion::prelude::{Expr, JoinType};
 
 use crate::operator::LogicalOperator::{LogicalJoin, LogicalProjection, LogicalScan};
 use crate::operator::Operator::{Logical, Physical};
-use crate::operator::PhysicalOperator::{PhysicalHashJoin, PhysicalTableScan};
-use crate::operator::{Join, Limit, LogicalOperator, Operator, Projection, TableScan};
+use crate::operator::PhysicalOperator::{PhysicalHashAggregate, PhysicalHashJoin, PhysicalNestedLoopJoin, PhysicalSortAggregate, PhysicalSortMergeJoin, PhysicalTableScan};
+use crate::operator::{Aggregate, HashAggregate, HashJoin, Join, Limit, LogicalOperator, NestedLoopJoin, Operator, Projection, SortAggregate, SortMergeJoin, TableScan};
 use crate::properties::{LogicalProperty, PhysicalPropertySet};
 use crate::stat::Statistics;
 
@@ -15,6 +15,23 @@ pub type PlanNodeId = u32;
 
 pub type PlanNodeRef = Rc<PlanNode>;
 
+/// Generates fresh, monotonically increasing [`PlanNodeId`]s for a single conversion pass.
+pub struct PlanNodeIdGen {
+    next: PlanNodeId,
+}
+
+impl PlanNodeIdGen {
+    pub fn new() -> Self {
+        Self { next: 0 }
+    }
+
+    pub fn next(&mut self) -> PlanNodeId {
+        let id = self.next;
+        self.next += 1;
+        id
+    }
+}
+
 /// One node in a plan.
 ///
 /// This is used in both input and output of an optimizer. Given that we may have many different
@@ -79,6 +96,41 @@ impl Iterator for BFSPlanNodeIter {
     }
 }
 
+/// Post order iterator of a single root dag plan: a node is only yielded once all of its inputs
+/// have been yielded, so callers can assemble a bottom-up rewrite of the plan (or of anything
+/// keyed by it) without recursing - the one place a plan's depth could otherwise overflow the
+/// call stack.
+struct PostOrderPlanNodeIter {
+    visited: HashSet<PlanNodeId>,
+    // `true` once a node's inputs have been pushed and it is ready to be yielded.
+    stack: Vec<(PlanNodeRef, bool)>,
+}
+
+impl Iterator for PostOrderPlanNodeIter {
+    type Item = PlanNodeRef;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((node, expanded)) = self.stack.pop() {
+            if expanded {
+                return Some(node);
+            }
+
+            if !self.visited.insert(node.id) {
+                continue;
+            }
+
+            self.stack.push((node.clone(), true));
+            for input in &node.inputs {
+                if !self.visited.contains(&input.id) {
+                    self.stack.push((input.clone(), false));
+                }
+            }
+        }
+
+        None
+    }
+}
+
 impl Plan {
     pub fn new(root: PlanNodeRef) -> Self {
         Self { root }
@@ -98,6 +150,13 @@ impl Plan {
             visited,
         }
     }
+
+    pub fn post_order_iterator(&self) -> impl Iterator<Item = PlanNodeRef> {
+        PostOrderPlanNodeIter {
+            visited: HashSet::new(),
+            stack: vec![(self.root.clone(), false)],
+        }
+    }
 }
 
 impl PlanNode {
@@ -124,6 +183,13 @@ impl PlanNode {
         &self.inputs
     }
 
+    /// Mutable access to this node's inputs, for rewrites that can mutate a uniquely-owned node
+    /// in place instead of rebuilding it via [`PlanNodeBuilder`] (see
+    /// [`crate::heuristic::OptimizerRule::try_optimize_owned`]).
+    pub fn inputs_mut(&mut self) -> &mut Vec<PlanNodeRef> {
+        &mut self.inputs
+    }
+
     pub fn logical_prop(&self) -> Option<&LogicalProperty> {
         self.logical_prop.as_ref()
     }
@@ -249,6 +315,17 @@ impl LogicalPlanBuilder {
         self.reset_root(plan_node)
     }
 
+    pub fn aggregate(&mut self, group_by: Vec<Expr>, aggr_expr: Vec<Expr>) -> &mut Self {
+        let aggregate = Aggregate::new(group_by, aggr_expr);
+        let plan_node = Rc::new(PlanNode::new(
+            self.next_plan_node_id,
+            Logical(LogicalOperator::LogicalAggregate(aggregate)),
+            vec![self.root.clone().unwrap()],
+        ));
+
+        self.reset_root(plan_node)
+    }
+
     /// Consume current plan, but not rest state, e.g. plan node id.
     ///
     /// This is useful for building multi child plan, e.g. join.
@@ -290,7 +367,7 @@ impl PhysicalPlanBuilder {
     }
 
     pub fn hash_join(mut self, join_type: JoinType, condition: Expr, right: PlanNodeRef) -> Self {
-        let join = Join::new(join_type, condition);
+        let join = HashJoin::new(join_type, condition);
         let plan_node = Rc::new(PlanNode::new(
             self.next_plan_node_id,
             Physical(PhysicalHashJoin(join)),
@@ -302,6 +379,58 @@ impl PhysicalPlanBuilder {
         self
     }
 
+    pub fn sort_merge_join(mut self, join_type: JoinType, condition: Expr, right: PlanNodeRef) -> Self {
+        let join = SortMergeJoin::new(join_type, condition);
+        let plan_node = Rc::new(PlanNode::new(
+            self.next_plan_node_id,
+            Physical(PhysicalSortMergeJoin(join)),
+            vec![self.root.clone(), right],
+        ));
+
+        self.reset_root(plan_node);
+
+        self
+    }
+
+    pub fn nested_loop_join(mut self, join_type: JoinType, condition: Expr, right: PlanNodeRef) -> Self {
+        let join = NestedLoopJoin::new(join_type, condition);
+        let plan_node = Rc::new(PlanNode::new(
+            self.next_plan_node_id,
+            Physical(PhysicalNestedLoopJoin(join)),
+            vec![self.root.clone(), right],
+        ));
+
+        self.reset_root(plan_node);
+
+        self
+    }
+
+    pub fn hash_aggregate(mut self, group_by: Vec<Expr>, aggr_expr: Vec<Expr>) -> Self {
+        let aggregate = HashAggregate::new(group_by, aggr_expr);
+        let plan_node = Rc::new(PlanNode::new(
+            self.next_plan_node_id,
+            Physical(PhysicalHashAggregate(aggregate)),
+            vec![self.root.clone()],
+        ));
+
+        self.reset_root(plan_node);
+
+        self
+    }
+
+    pub fn sort_aggregate(mut self, group_by: Vec<Expr>, aggr_expr: Vec<Expr>) -> Self {
+        let aggregate = SortAggregate::new(group_by, aggr_expr);
+        let plan_node = Rc::new(PlanNode::new(
+            self.next_plan_node_id,
+            Physical(PhysicalSortAggregate(aggregate)),
+            vec![self.root.clone()],
+        ));
+
+        self.reset_root(plan_node);
+
+        self
+    }
+
     pub fn build(self) -> Plan {
         Plan { root: self.root }
     }
@@ -0,0 +1,23 @@
+use crate::properties::PhysicalProp;
+
+/// Required or provided data distribution across worker partitions.
+#[derive(Clone, Debug, Hash, Eq, PartialEq)]
+pub enum DistributionProp {
+    /// No distribution requirement/guarantee.
+    Any,
+    /// A single partition holds all the data.
+    Single,
+    /// Data is hash partitioned on the given columns.
+    HashPartitioned(Vec<String>),
+    /// Every partition holds a full copy of the data.
+    Broadcast,
+}
+
+impl PhysicalProp for DistributionProp {
+    fn satisfies(&self, other: &Self) -> bool {
+        match other {
+            DistributionProp::Any => true,
+            other => self == other,
+        }
+    }
+}
@@ -1,7 +1,14 @@
 use std::rc::Rc;
 
+use datafusion::logical_expr::ExprSchemable;
 use datafusion::logical_plan::DFSchema;
 
+use crate::operator::LogicalOperator::{LogicalAggregate, LogicalFilter, LogicalJoin, LogicalLimit, LogicalProjection};
+use crate::operator::Operator::{Logical, Physical};
+use crate::operator::PhysicalOperator::{PhysicalHashAggregate, PhysicalHashJoin, PhysicalNestedLoopJoin, PhysicalSortAggregate, PhysicalSortMergeJoin};
+use crate::operator::Operator;
+use crate::Expr;
+
 #[derive(Clone, PartialEq, Debug)]
 pub struct LogicalProperty {
     schema: Rc<DFSchema>,
@@ -18,3 +25,57 @@ impl LogicalProperty {
         &self.schema
     }
 }
+
+/// Derives an operator's output [`LogicalProperty`] (currently just its schema) from its
+/// already-derived inputs', bottom-up - the schema companion to
+/// [`crate::stat::derive_statistics`].
+///
+/// Returns `None` when there isn't enough information to derive one, e.g. an input whose own
+/// schema is unknown, or an operator - `TableScan`, `EmptyRelation` - that carries no input to
+/// derive one from and must instead have its schema set directly by whoever constructs it (the
+/// datafusion boundary conversion for a `TableScan`; the rule that builds it, by carrying forward
+/// the schema of the subtree it replaced, for an `EmptyRelation`).
+pub fn derive_logical_prop(operator: &Operator, input_props: &[Option<LogicalProperty>]) -> Option<LogicalProperty> {
+    match operator {
+        // None of these change the set of output columns, so the input's schema applies as-is.
+        // `Projection`'s schema can in general differ (computed/renamed columns); its expressions
+        // are not resolved to output fields here, so the input's schema is passed through as-is.
+        Logical(LogicalFilter(_)) | Logical(LogicalLimit(_)) | Logical(LogicalProjection(_)) => {
+            input_props.first().cloned().flatten()
+        }
+        Logical(LogicalJoin(_))
+        | Physical(PhysicalHashJoin(_))
+        | Physical(PhysicalSortMergeJoin(_))
+        | Physical(PhysicalNestedLoopJoin(_)) => {
+            let left = input_props.first()?.as_ref()?;
+            let right = input_props.get(1)?.as_ref()?;
+            let schema = left.schema().join(right.schema()).ok()?;
+            Some(LogicalProperty::new(schema))
+        }
+        // An aggregate's output is its group-by columns followed by its aggregate expressions,
+        // each resolved against the input's schema - mirroring how datafusion itself derives an
+        // `Aggregate` plan node's schema.
+        Logical(LogicalAggregate(aggregate)) => {
+            derive_aggregate_schema(aggregate.group_by(), aggregate.aggr_expr(), input_props.first()?.as_ref()?)
+        }
+        Physical(PhysicalHashAggregate(aggregate)) => {
+            derive_aggregate_schema(aggregate.group_by(), aggregate.aggr_expr(), input_props.first()?.as_ref()?)
+        }
+        Physical(PhysicalSortAggregate(aggregate)) => {
+            derive_aggregate_schema(aggregate.group_by(), aggregate.aggr_expr(), input_props.first()?.as_ref()?)
+        }
+        _ => None,
+    }
+}
+
+/// Resolves `group_by` followed by `aggr_expr` against `input`'s schema into the aggregate's
+/// output schema - "group keys + aggregates", in that order.
+fn derive_aggregate_schema(group_by: &[Expr], aggr_expr: &[Expr], input: &LogicalProperty) -> Option<LogicalProperty> {
+    let fields = group_by
+        .iter()
+        .chain(aggr_expr.iter())
+        .map(|expr| expr.to_field(input.schema()))
+        .collect::<datafusion::error::Result<Vec<_>>>()
+        .ok()?;
+    DFSchema::new_with_metadata(fields, Default::default()).ok().map(LogicalProperty::new)
+}
@@ -0,0 +1,26 @@
+use crate::properties::PhysicalProp;
+
+/// Required or provided sort order, expressed as an ordered list of column names.
+///
+/// A provided order satisfies a required order if it is sorted on at least the required columns,
+/// in the required order, as a prefix of its own columns.
+#[derive(Clone, Debug, Hash, Eq, PartialEq)]
+pub struct OrderProp {
+    columns: Vec<String>,
+}
+
+impl OrderProp {
+    pub fn new(columns: Vec<String>) -> Self {
+        Self { columns }
+    }
+
+    pub fn columns(&self) -> &[String] {
+        &self.columns
+    }
+}
+
+impl PhysicalProp for OrderProp {
+    fn satisfies(&self, other: &Self) -> bool {
+        other.columns.len() <= self.columns.len() && self.columns[..other.columns.len()] == other.columns[..]
+    }
+}
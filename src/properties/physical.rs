@@ -0,0 +1,35 @@
+use crate::properties::{DistributionProp, OrderProp, PhysicalProp};
+
+/// A concrete bundle of physical properties a physical expression exposes or requires, e.g. a
+/// particular sort order together with a particular data distribution.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PhysicalPropertySet {
+    order: Option<OrderProp>,
+    distribution: Option<DistributionProp>,
+}
+
+impl PhysicalPropertySet {
+    pub fn new(order: Option<OrderProp>, distribution: Option<DistributionProp>) -> Self {
+        Self { order, distribution }
+    }
+
+    pub fn order(&self) -> Option<&OrderProp> {
+        self.order.as_ref()
+    }
+
+    pub fn distribution(&self) -> Option<&DistributionProp> {
+        self.distribution.as_ref()
+    }
+
+    /// Whether `self`, as a provided property set, satisfies `required`.
+    pub fn satisfies(&self, required: &PhysicalPropertySet) -> bool {
+        required
+            .order
+            .as_ref()
+            .map_or(true, |req| self.order.as_ref().map_or(false, |o| o.satisfies(req)))
+            && required
+                .distribution
+                .as_ref()
+                .map_or(true, |req| self.distribution.as_ref().map_or(false, |d| d.satisfies(req)))
+    }
+}
@@ -0,0 +1,130 @@
+use std::rc::Rc;
+
+use crate::error::OptResult;
+use crate::operator::LogicalOperator::{LogicalEmptyRelation, LogicalLimit};
+use crate::operator::Operator::Logical;
+use crate::operator::EmptyRelation;
+use crate::optimizer::{OptExpr, Optimizer};
+use crate::rules::OptExprNode::{ExprHandleNode, OperatorNode};
+use crate::rules::{any, pattern, OptExpression, PatterBuilder, Pattern, Rule};
+
+lazy_static! {
+    static ref PATTERN: Rc<Pattern> = Rc::new(
+        pattern(|op| matches!(op, Logical(LogicalLimit(_))))
+            .leaf(any)
+            .finish()
+    );
+}
+
+/// Removes a `Limit` that can never trim anything: a `Limit(0)` becomes an empty relation, and a
+/// `Limit(n)` whose input is known (via its derived `Statistics`) to already produce at most `n`
+/// rows is simply dropped.
+#[derive(Clone, Debug)]
+pub struct EliminateLimitRule;
+
+impl Rule for EliminateLimitRule {
+    fn pattern(&self) -> Rc<Pattern> {
+        PATTERN.clone()
+    }
+
+    fn apply<O: Optimizer>(&self, input: OptExpression<O>, optimizer: &O) -> OptResult<Option<OptExpression<O>>> {
+        let schema = input.logical_prop().cloned();
+        let (node, mut inputs) = input.into_parts();
+        let limit = match &node {
+            OperatorNode(Logical(LogicalLimit(limit))) => limit.clone(),
+            _ => return Ok(None),
+        };
+        let child = inputs.remove(0);
+
+        if limit.limit() == 0 {
+            return Ok(Some(
+                OptExpression::new(
+                    OperatorNode(Logical(LogicalEmptyRelation(EmptyRelation::default()))),
+                    vec![],
+                )
+                .with_logical_prop(schema),
+            ));
+        }
+
+        let child_handle = match child.node() {
+            ExprHandleNode(handle) => Some(*handle),
+            _ => None,
+        };
+        if let Some(handle) = child_handle {
+            if let Some(stat) = optimizer.expr_at(handle).stat() {
+                if stat.row_count() <= limit.limit() {
+                    // The input never produces more rows than the limit; the limit is redundant.
+                    return Ok(Some(child));
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn name(&self) -> String {
+        "EliminateLimitRule".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+    use std::sync::Arc;
+
+    use datafusion::logical_plan::DFSchema;
+
+    use crate::heuristic::{HepBatch, HepBatchStrategy, HepOptimizer, MatchOrder};
+    use crate::operator::LogicalOperator::{LogicalEmptyRelation, LogicalLimit, LogicalScan};
+    use crate::operator::Operator::Logical;
+    use crate::operator::{Limit, TableScan};
+    use crate::optimizer::{DefaultOptimizerContext, Optimizer, OptimizerContext};
+    use crate::plan::{Plan, PlanNodeBuilder};
+    use crate::properties::LogicalProperty;
+    use crate::rules::{EliminateLimitRule, RuleImpl};
+
+    fn run(plan: Plan) -> Plan {
+        let context: Arc<dyn OptimizerContext> = Arc::new(DefaultOptimizerContext::default());
+        let batch = HepBatch::new(
+            vec![RuleImpl::EliminateLimit(EliminateLimitRule)],
+            MatchOrder::BottomUp,
+            HepBatchStrategy::Once,
+        );
+        HepOptimizer::new(vec![batch], plan, context).find_best_plan().unwrap()
+    }
+
+    #[test]
+    fn limit_zero_becomes_empty_relation_with_same_schema() {
+        let schema = DFSchema::new_with_metadata(vec![], Default::default()).unwrap();
+        let scan = Rc::new(
+            PlanNodeBuilder::new(0, &Logical(LogicalScan(TableScan::new("t"))))
+                .with_logical_prop(Some(LogicalProperty::new(schema.clone())))
+                .build(),
+        );
+        let limit = Rc::new(
+            PlanNodeBuilder::new(1, &Logical(LogicalLimit(Limit::new(0))))
+                .add_inputs(vec![scan])
+                .build(),
+        );
+
+        let optimized = run(Plan::new(limit));
+        let root = optimized.root();
+
+        assert!(matches!(root.operator(), Logical(LogicalEmptyRelation(_))));
+        assert_eq!(root.logical_prop().unwrap().schema(), &schema);
+    }
+
+    #[test]
+    fn limit_above_known_row_count_is_dropped() {
+        let scan = Rc::new(PlanNodeBuilder::new(0, &Logical(LogicalScan(TableScan::with_limit("t", 5)))).build());
+        let limit = Rc::new(
+            PlanNodeBuilder::new(1, &Logical(LogicalLimit(Limit::new(10))))
+                .add_inputs(vec![scan])
+                .build(),
+        );
+
+        let optimized = run(Plan::new(limit));
+
+        assert!(matches!(optimized.root().operator(), Logical(LogicalScan(_))));
+    }
+}
@@ -0,0 +1,61 @@
+use std::rc::Rc;
+
+use crate::error::OptResult;
+use crate::operator::LogicalOperator::{LogicalLimit, LogicalScan};
+use crate::operator::Operator::Logical;
+use crate::operator::TableScan;
+use crate::optimizer::{OptExpr, Optimizer};
+use crate::rules::OptExprNode::{ExprHandleNode, OperatorNode};
+use crate::rules::{pattern, OptExpression, PatterBuilder, Pattern, Rule};
+
+lazy_static! {
+    static ref PATTERN: Rc<Pattern> = Rc::new(
+        pattern(|op| matches!(op, Logical(LogicalLimit(_))))
+            .leaf(|op| matches!(op, Logical(LogicalScan(_))))
+            .finish()
+    );
+}
+
+/// Folds a `Limit` sitting directly over a `TableScan` into the scan's own limit, dropping the
+/// now-redundant `Limit` node.
+#[derive(Clone, Debug)]
+pub struct LimitPushDownRule;
+
+impl Rule for LimitPushDownRule {
+    fn pattern(&self) -> Rc<Pattern> {
+        PATTERN.clone()
+    }
+
+    fn apply<O: Optimizer>(&self, input: OptExpression<O>, optimizer: &O) -> OptResult<Option<OptExpression<O>>> {
+        let (node, mut inputs) = input.into_parts();
+        let limit = match &node {
+            OperatorNode(Logical(LogicalLimit(limit))) => limit.clone(),
+            _ => return Ok(None),
+        };
+        let scan_child = inputs.remove(0);
+        let scan_logical_prop = scan_child.logical_prop().cloned();
+        let scan_handle = match scan_child.node() {
+            ExprHandleNode(handle) => *handle,
+            _ => return Ok(None),
+        };
+        let scan = match optimizer.expr_at(scan_handle).operator() {
+            Logical(LogicalScan(scan)) => scan.clone(),
+            _ => return Ok(None),
+        };
+
+        // The scan already has a tighter (or equal) limit; nothing to fold in.
+        if scan.limit().map_or(false, |existing| existing <= limit.limit()) {
+            return Ok(None);
+        }
+
+        let new_scan = TableScan::with_limit(scan.table_name(), limit.limit());
+        Ok(Some(
+            OptExpression::new(OperatorNode(Logical(LogicalScan(new_scan))), vec![])
+                .with_logical_prop(scan_logical_prop),
+        ))
+    }
+
+    fn name(&self) -> String {
+        "LimitPushDownRule".to_string()
+    }
+}
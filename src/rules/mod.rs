@@ -0,0 +1,165 @@
+//! Optimization rules.
+//!
+//! A rule matches a [`Pattern`] against a subtree of a plan and, if it fires, returns a
+//! replacement subtree. Rules are written against the [`crate::optimizer::Optimizer`] abstraction
+//! rather than a concrete optimizer, so the same [`Rule`] impl can run under the heuristic
+//! optimizer or the cascades optimizer.
+
+mod pattern;
+pub use pattern::*;
+mod push_down_filter;
+pub use push_down_filter::*;
+mod limit_push_down;
+pub use limit_push_down::*;
+mod eliminate_limit;
+pub use eliminate_limit::*;
+mod propagate_empty_relation;
+pub use propagate_empty_relation::*;
+mod select_aggregate_strategy;
+pub use select_aggregate_strategy::*;
+mod select_join_strategy;
+pub use select_join_strategy::*;
+
+use std::fmt::Debug;
+use std::rc::Rc;
+
+use crate::error::OptResult;
+use crate::operator::Operator;
+use crate::optimizer::Optimizer;
+use crate::properties::LogicalProperty;
+
+/// A node in a rule's replacement subtree.
+///
+/// Most of a replacement is unchanged, so rules are expected to reuse existing nodes/groups by
+/// handle (`ExprHandleNode`/`GroupHandleNode`) and only build fresh [`Operator`]s
+/// (`OperatorNode`) where they actually rewrote something.
+pub enum OptExprNode<O: Optimizer> {
+    ExprHandleNode(O::ExprHandle),
+    GroupHandleNode(O::GroupHandle),
+    OperatorNode(Operator),
+}
+
+/// A (possibly partially shared) relational expression produced by a rule.
+pub struct OptExpression<O: Optimizer> {
+    node: OptExprNode<O>,
+    inputs: Vec<OptExpression<O>>,
+    /// This node's already-known [`LogicalProperty`], when the caller has one on hand.
+    ///
+    /// Set by [`crate::heuristic::binding::bind`] on every node of a match, from the bound
+    /// expression's own derived property, since a rule's matched root has no `ExprHandle` of its
+    /// own to look that property up through (only its children do). A rule that builds a fresh,
+    /// childless replacement whose schema can't be rederived from inputs (e.g. an
+    /// `EmptyRelation` standing in for the subtree it replaced) reads this off the node(s) it
+    /// consumed and carries it forward onto the replacement via [`Self::with_logical_prop`].
+    logical_prop: Option<LogicalProperty>,
+}
+
+impl<O: Optimizer> OptExpression<O> {
+    pub fn new(node: OptExprNode<O>, inputs: Vec<OptExpression<O>>) -> Self {
+        Self { node, inputs, logical_prop: None }
+    }
+
+    pub fn node(&self) -> &OptExprNode<O> {
+        &self.node
+    }
+
+    pub fn inputs(&self) -> &[OptExpression<O>] {
+        &self.inputs
+    }
+
+    pub fn logical_prop(&self) -> Option<&LogicalProperty> {
+        self.logical_prop.as_ref()
+    }
+
+    /// Attaches an explicit `LogicalProperty` to this node, overriding whatever the graph would
+    /// otherwise derive for it from its inputs.
+    pub(crate) fn with_logical_prop(mut self, logical_prop: Option<LogicalProperty>) -> Self {
+        self.logical_prop = logical_prop;
+        self
+    }
+
+    /// Consumes `self`, handing back its node and inputs by value so a rule can rebuild a
+    /// rewritten subtree out of pieces of the one it matched.
+    pub(crate) fn into_parts(self) -> (OptExprNode<O>, Vec<OptExpression<O>>) {
+        (self.node, self.inputs)
+    }
+}
+
+/// A single optimization rule.
+///
+/// `apply` is generic over the optimizer so the same rule works whether it's driven by the
+/// heuristic optimizer or the cascades optimizer.
+///
+/// Following the datafusion convention, `apply` returns `Ok(None)` to mean "this rule made no
+/// change" and `Ok(Some(new_expr))` to mean "here is the rewritten subtree". The heuristic driver
+/// uses this signal directly to detect a fixed point, rather than comparing whole plans
+/// structurally via `PlanNode`'s `PartialEq`, which would otherwise require walking every
+/// operator, input, and derived property on every iteration.
+pub trait Rule: Debug {
+    /// The shape of subtree this rule matches against.
+    fn pattern(&self) -> Rc<Pattern>;
+
+    /// Rewrites the matched subtree, or returns `Ok(None)` if the rule does not fire for this
+    /// match.
+    fn apply<O: Optimizer>(&self, input: OptExpression<O>, optimizer: &O) -> OptResult<Option<OptExpression<O>>>;
+
+    /// This rule's name, looked up against [`crate::optimizer::OptimizerContext::is_rule_enabled`]
+    /// to decide whether it's allowed to run. Each concrete rule overrides this with a stable
+    /// slug matching its type name (e.g. `PushDownFilterRule`); the default falls back to the
+    /// type's `Debug` representation, which for [`RuleImpl`] is the wrapping variant's own debug
+    /// output (e.g. `PushDownFilter(PushDownFilterRule)`), not that slug, so `RuleImpl` and every
+    /// concrete rule override this instead of relying on the default.
+    fn name(&self) -> String {
+        format!("{:?}", self)
+    }
+}
+
+/// Dispatch enum over all concrete [`Rule`] implementations reachable from [`crate::heuristic::HepOptimizer`].
+///
+/// Rules are collected into `Vec<RuleImpl>` rather than `Vec<Box<dyn Rule>>` so they stay
+/// `Clone`, which the heuristic driver relies on to try the same rule against many nodes.
+///
+/// [`SelectJoinStrategyRule`] and [`SelectAggregateStrategyRule`] are deliberately not variants
+/// here: they pick a single physical alternative for a cost-based search to choose between, but
+/// the only such search this crate is meant to feed, the cascades optimizer, doesn't exist yet
+/// (`cascades` in `lib.rs` is a placeholder module). The heuristic driver's only way back out to
+/// datafusion, `build_df_logical_plan`, also bails on any `Physical(..)` operator, so a `HepBatch`
+/// running these rules would produce a plan nothing downstream can convert. They stay as ordinary
+/// [`Rule`] impls, usable directly or added back to this enum once the cascades path exists to
+/// receive their output.
+#[derive(Clone, Debug)]
+pub enum RuleImpl {
+    PushDownFilter(PushDownFilterRule),
+    LimitPushDown(LimitPushDownRule),
+    EliminateLimit(EliminateLimitRule),
+    PropagateEmptyRelation(PropagateEmptyRelationRule),
+}
+
+impl Rule for RuleImpl {
+    fn pattern(&self) -> Rc<Pattern> {
+        match self {
+            RuleImpl::PushDownFilter(rule) => rule.pattern(),
+            RuleImpl::LimitPushDown(rule) => rule.pattern(),
+            RuleImpl::EliminateLimit(rule) => rule.pattern(),
+            RuleImpl::PropagateEmptyRelation(rule) => rule.pattern(),
+        }
+    }
+
+    fn apply<O: Optimizer>(&self, input: OptExpression<O>, optimizer: &O) -> OptResult<Option<OptExpression<O>>> {
+        match self {
+            RuleImpl::PushDownFilter(rule) => rule.apply(input, optimizer),
+            RuleImpl::LimitPushDown(rule) => rule.apply(input, optimizer),
+            RuleImpl::EliminateLimit(rule) => rule.apply(input, optimizer),
+            RuleImpl::PropagateEmptyRelation(rule) => rule.apply(input, optimizer),
+        }
+    }
+
+    fn name(&self) -> String {
+        match self {
+            RuleImpl::PushDownFilter(rule) => rule.name(),
+            RuleImpl::LimitPushDown(rule) => rule.name(),
+            RuleImpl::EliminateLimit(rule) => rule.name(),
+            RuleImpl::PropagateEmptyRelation(rule) => rule.name(),
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use std::rc::Rc;
+
+use datafusion::prelude::JoinType;
+
+use crate::error::OptResult;
+use crate::operator::LogicalOperator::{LogicalEmptyRelation, LogicalJoin, LogicalProjection};
+use crate::operator::Operator::Logical;
+use crate::operator::EmptyRelation;
+use crate::optimizer::{OptExpr, Optimizer};
+use crate::rules::OptExprNode::{ExprHandleNode, OperatorNode};
+use crate::rules::{pattern, OptExpression, PatterBuilder, Pattern, Rule};
+
+lazy_static! {
+    static ref PATTERN: Rc<Pattern> = Rc::new(
+        pattern(|op| matches!(op, Logical(LogicalProjection(_)) | Logical(LogicalJoin(_)))).finish()
+    );
+}
+
+/// Propagates an empty relation upward: a `Projection` over an empty relation, or an inner `Join`
+/// with an empty side, is itself empty.
+#[derive(Clone, Debug)]
+pub struct PropagateEmptyRelationRule;
+
+impl Rule for PropagateEmptyRelationRule {
+    fn pattern(&self) -> Rc<Pattern> {
+        PATTERN.clone()
+    }
+
+    fn apply<O: Optimizer>(&self, input: OptExpression<O>, optimizer: &O) -> OptResult<Option<OptExpression<O>>> {
+        let schema = input.logical_prop().cloned();
+        let (node, inputs) = input.into_parts();
+        let operator = match &node {
+            OperatorNode(operator) => operator.clone(),
+            _ => return Ok(None),
+        };
+
+        let has_empty_child = inputs.iter().any(|child| match child.node() {
+            ExprHandleNode(handle) => matches!(optimizer.expr_at(*handle).operator(), Logical(LogicalEmptyRelation(_))),
+            _ => false,
+        });
+
+        if !has_empty_child {
+            return Ok(None);
+        }
+
+        let becomes_empty = match &operator {
+            Logical(LogicalProjection(_)) => true,
+            Logical(LogicalJoin(join)) => join.join_type() == JoinType::Inner,
+            _ => false,
+        };
+
+        if becomes_empty {
+            Ok(Some(
+                OptExpression::new(
+                    OperatorNode(Logical(LogicalEmptyRelation(EmptyRelation::default()))),
+                    vec![],
+                )
+                .with_logical_prop(schema),
+            ))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn name(&self) -> String {
+        "PropagateEmptyRelationRule".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+    use std::sync::Arc;
+
+    use datafusion::common::ScalarValue;
+    use datafusion::logical_plan::DFSchema;
+    use datafusion::prelude::JoinType;
+
+    use crate::heuristic::{HepBatch, HepBatchStrategy, HepOptimizer, MatchOrder};
+    use crate::operator::LogicalOperator::{LogicalEmptyRelation, LogicalJoin, LogicalScan};
+    use crate::operator::Operator::Logical;
+    use crate::operator::{EmptyRelation, Join, TableScan};
+    use crate::optimizer::{DefaultOptimizerContext, Optimizer, OptimizerContext};
+    use crate::plan::{Plan, PlanNodeBuilder};
+    use crate::properties::LogicalProperty;
+    use crate::rules::{PropagateEmptyRelationRule, RuleImpl};
+    use crate::Expr;
+
+    #[test]
+    fn inner_join_with_empty_side_becomes_empty() {
+        let schema = DFSchema::new_with_metadata(vec![], Default::default()).unwrap();
+        let empty = Rc::new(
+            PlanNodeBuilder::new(0, &Logical(LogicalEmptyRelation(EmptyRelation::default())))
+                .with_logical_prop(Some(LogicalProperty::new(schema.clone())))
+                .build(),
+        );
+        let scan = Rc::new(
+            PlanNodeBuilder::new(1, &Logical(LogicalScan(TableScan::new("t"))))
+                .with_logical_prop(Some(LogicalProperty::new(schema)))
+                .build(),
+        );
+        let join_condition = Expr::Literal(ScalarValue::Boolean(Some(true)));
+        let join = Rc::new(
+            PlanNodeBuilder::new(2, &Logical(LogicalJoin(Join::new(JoinType::Inner, join_condition))))
+                .add_inputs(vec![empty, scan])
+                .build(),
+        );
+
+        let context: Arc<dyn OptimizerContext> = Arc::new(DefaultOptimizerContext::default());
+        let batch = HepBatch::new(
+            vec![RuleImpl::PropagateEmptyRelation(PropagateEmptyRelationRule)],
+            MatchOrder::BottomUp,
+            HepBatchStrategy::Once,
+        );
+        let optimized = HepOptimizer::new(vec![batch], Plan::new(join), context).find_best_plan().unwrap();
+
+        assert!(matches!(optimized.root().operator(), Logical(LogicalEmptyRelation(_))));
+    }
+}
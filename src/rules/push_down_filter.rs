@@ -0,0 +1,244 @@
+use std::collections::HashSet;
+use std::rc::Rc;
+
+use datafusion::common::Column;
+use datafusion::logical_expr::{and, Expr};
+use datafusion::logical_plan::Operator as DFOperator;
+use datafusion::prelude::JoinType;
+
+use crate::error::OptResult;
+use crate::operator::LogicalOperator::{LogicalFilter, LogicalJoin};
+use crate::operator::Operator::Logical;
+use crate::operator::Filter;
+use crate::optimizer::{OptExpr, Optimizer};
+use crate::rules::OptExprNode::{ExprHandleNode, OperatorNode};
+use crate::rules::{any, pattern, OptExpression, PatterBuilder, Pattern, Rule};
+
+lazy_static! {
+    static ref PATTERN: Rc<Pattern> = Rc::new(
+        pattern(|op| matches!(op, Logical(LogicalFilter(_))))
+            .pattern(|op| matches!(op, Logical(LogicalJoin(_))))
+            .leaf(any)
+            .leaf(any)
+            .finish()
+            .finish()
+    );
+}
+
+/// Pushes a filter sitting above an inner join down to whichever join input each of its conjuncts
+/// references exclusively, keeping the conjuncts that can't be proven safe to push above the
+/// join. Filters above any other join type are left where they are.
+#[derive(Clone, Debug)]
+pub struct PushDownFilterRule;
+
+impl Rule for PushDownFilterRule {
+    fn pattern(&self) -> Rc<Pattern> {
+        PATTERN.clone()
+    }
+
+    fn apply<O: Optimizer>(&self, input: OptExpression<O>, optimizer: &O) -> OptResult<Option<OptExpression<O>>> {
+        let (node, mut inputs) = input.into_parts();
+        let filter = match &node {
+            OperatorNode(Logical(LogicalFilter(filter))) => filter.clone(),
+            _ => return Ok(None),
+        };
+        let join_expr = inputs.remove(0);
+        let (join_node, mut join_inputs) = join_expr.into_parts();
+        let join = match &join_node {
+            OperatorNode(Logical(LogicalJoin(join))) => join.clone(),
+            _ => return Ok(None),
+        };
+        // Below an outer join, a pushed predicate would drop rows the join should null-extend.
+        if join.join_type() != JoinType::Inner {
+            return Ok(None);
+        }
+        let right_child = join_inputs.remove(1);
+        let left_child = join_inputs.remove(0);
+
+        let left_handle = match left_child.node() {
+            ExprHandleNode(handle) => *handle,
+            _ => return Ok(None),
+        };
+        let right_handle = match right_child.node() {
+            ExprHandleNode(handle) => *handle,
+            _ => return Ok(None),
+        };
+
+        // Without a derived schema for one of the sides, leave the filter where it is rather than
+        // risk pushing a predicate to the wrong side.
+        let (left_schema, right_schema) = match (
+            optimizer.expr_at(left_handle).logical_prop(),
+            optimizer.expr_at(right_handle).logical_prop(),
+        ) {
+            (Some(l), Some(r)) => (l.schema().clone(), r.schema().clone()),
+            _ => return Ok(None),
+        };
+
+        let mut left_preds = vec![];
+        let mut right_preds = vec![];
+        let mut remaining_preds = vec![];
+        for conjunct in split_conjunction(filter.predicate()) {
+            match columns_in(&conjunct) {
+                Some(columns) if columns.iter().all(|c| left_schema.index_of_column(c).is_ok()) => {
+                    left_preds.push(conjunct)
+                }
+                Some(columns) if columns.iter().all(|c| right_schema.index_of_column(c).is_ok()) => {
+                    right_preds.push(conjunct)
+                }
+                _ => remaining_preds.push(conjunct),
+            }
+        }
+
+        if left_preds.is_empty() && right_preds.is_empty() {
+            return Ok(None);
+        }
+
+        let new_left = wrap_in_filter(left_child, left_preds);
+        let new_right = wrap_in_filter(right_child, right_preds);
+        let new_join = OptExpression::new(OperatorNode(Logical(LogicalJoin(join))), vec![new_left, new_right]);
+
+        let result = match conjoin(remaining_preds) {
+            Some(predicate) => OptExpression::new(OperatorNode(Logical(LogicalFilter(Filter::new(predicate)))), vec![new_join]),
+            None => new_join,
+        };
+
+        Ok(Some(result))
+    }
+
+    fn name(&self) -> String {
+        "PushDownFilterRule".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+    use std::sync::Arc;
+
+    use datafusion::arrow::datatypes::{DataType, Field, Schema};
+    use datafusion::common::ScalarValue;
+    use datafusion::logical_expr::and;
+    use datafusion::logical_plan::DFSchema;
+    use datafusion::logical_plan::Operator as DFOperator;
+    use datafusion::prelude::JoinType;
+
+    use crate::heuristic::{HepBatch, HepBatchStrategy, HepOptimizer, MatchOrder};
+    use crate::operator::LogicalOperator::{LogicalFilter, LogicalJoin, LogicalScan};
+    use crate::operator::Operator::Logical;
+    use crate::operator::{Filter, Join, TableScan};
+    use crate::optimizer::{DefaultOptimizerContext, Optimizer, OptimizerContext};
+    use crate::plan::{Plan, PlanNodeBuilder};
+    use crate::properties::LogicalProperty;
+    use crate::rules::{PushDownFilterRule, RuleImpl};
+    use crate::Expr;
+
+    fn schema_with(column: &str) -> DFSchema {
+        let arrow_schema = Schema::new(vec![Field::new(column, DataType::Int64, false)]);
+        DFSchema::try_from(arrow_schema).unwrap()
+    }
+
+    fn column(name: &str) -> Expr {
+        Expr::Column(datafusion::common::Column::from_name(name))
+    }
+
+    fn gt_one(column_name: &str) -> Expr {
+        Expr::BinaryExpr {
+            left: Box::new(column(column_name)),
+            op: DFOperator::Gt,
+            right: Box::new(Expr::Literal(ScalarValue::Int64(Some(1)))),
+        }
+    }
+
+    #[test]
+    fn pushes_single_side_conjuncts_and_keeps_cross_side_remainder_above() {
+        let left_scan = Rc::new(
+            PlanNodeBuilder::new(0, &Logical(LogicalScan(TableScan::new("t1"))))
+                .with_logical_prop(Some(LogicalProperty::new(schema_with("a"))))
+                .build(),
+        );
+        let right_scan = Rc::new(
+            PlanNodeBuilder::new(1, &Logical(LogicalScan(TableScan::new("t2"))))
+                .with_logical_prop(Some(LogicalProperty::new(schema_with("b"))))
+                .build(),
+        );
+
+        let left_only = gt_one("a");
+        let right_only = gt_one("b");
+        let cross_side = Expr::BinaryExpr {
+            left: Box::new(column("a")),
+            op: DFOperator::Eq,
+            right: Box::new(column("b")),
+        };
+        let predicate = and(and(left_only, right_only), cross_side);
+
+        let join = Rc::new(
+            PlanNodeBuilder::new(
+                2,
+                &Logical(LogicalJoin(Join::new(JoinType::Inner, Expr::Literal(ScalarValue::Boolean(Some(true)))))),
+            )
+            .add_inputs(vec![left_scan, right_scan])
+            .build(),
+        );
+        let filter = Rc::new(
+            PlanNodeBuilder::new(3, &Logical(LogicalFilter(Filter::new(predicate))))
+                .add_inputs(vec![join])
+                .build(),
+        );
+
+        let context: Arc<dyn OptimizerContext> = Arc::new(DefaultOptimizerContext::default());
+        let batch = HepBatch::new(
+            vec![RuleImpl::PushDownFilter(PushDownFilterRule)],
+            MatchOrder::BottomUp,
+            HepBatchStrategy::Once,
+        );
+        let optimized = HepOptimizer::new(vec![batch], Plan::new(filter), context).find_best_plan().unwrap();
+
+        // The cross-side conjunct can't be proven safe to push to either side, so it stays above
+        // the join as a residual filter; the single-side conjuncts move down onto their own side.
+        let root = optimized.root();
+        assert!(matches!(root.operator(), Logical(LogicalFilter(_))));
+
+        let join_node = &root.inputs()[0];
+        assert!(matches!(join_node.operator(), Logical(LogicalJoin(_))));
+        assert!(matches!(join_node.inputs()[0].operator(), Logical(LogicalFilter(_))));
+        assert!(matches!(join_node.inputs()[1].operator(), Logical(LogicalFilter(_))));
+    }
+}
+
+fn wrap_in_filter<O: Optimizer>(child: OptExpression<O>, preds: Vec<Expr>) -> OptExpression<O> {
+    match conjoin(preds) {
+        Some(predicate) => OptExpression::new(OperatorNode(Logical(LogicalFilter(Filter::new(predicate)))), vec![child]),
+        None => child,
+    }
+}
+
+fn conjoin(preds: Vec<Expr>) -> Option<Expr> {
+    preds.into_iter().reduce(and)
+}
+
+fn split_conjunction(expr: &Expr) -> Vec<Expr> {
+    match expr {
+        Expr::BinaryExpr { left, op, right } if matches!(op, DFOperator::And) => {
+            let mut preds = split_conjunction(left);
+            preds.extend(split_conjunction(right));
+            preds
+        }
+        other => vec![other.clone()],
+    }
+}
+
+/// Collects the columns `expr` references, or `None` if `expr` contains a shape we don't
+/// recognize, in which case the caller should treat it conservatively as unsafe to push down.
+fn columns_in(expr: &Expr) -> Option<HashSet<Column>> {
+    match expr {
+        Expr::Column(c) => Some(HashSet::from([c.clone()])),
+        Expr::Literal(_) => Some(HashSet::new()),
+        Expr::BinaryExpr { left, right, .. } => {
+            let mut columns = columns_in(left)?;
+            columns.extend(columns_in(right)?);
+            Some(columns)
+        }
+        Expr::Not(e) | Expr::IsNull(e) | Expr::IsNotNull(e) => columns_in(e),
+        _ => None,
+    }
+}
@@ -0,0 +1,78 @@
+use std::rc::Rc;
+
+use crate::error::OptResult;
+use crate::operator::LogicalOperator::LogicalAggregate;
+use crate::operator::Operator::{Logical, Physical};
+use crate::operator::PhysicalOperator::{PhysicalHashAggregate, PhysicalSortAggregate};
+use crate::operator::{column_names, HashAggregate, SortAggregate};
+use crate::optimizer::{OptExpr, Optimizer};
+use crate::properties::{OrderProp, PhysicalProp};
+use crate::rules::OptExprNode::{ExprHandleNode, OperatorNode};
+use crate::rules::{any, pattern, OptExpression, PatterBuilder, Pattern, Rule};
+
+lazy_static! {
+    static ref PATTERN: Rc<Pattern> = Rc::new(
+        pattern(|op| matches!(op, Logical(LogicalAggregate(_))))
+            .leaf(any)
+            .finish()
+    );
+}
+
+/// Picks a physical implementation for a `LogicalAggregate`: `SortAggregate` when its child
+/// already produces the group-by columns in order (so the sort is free), `HashAggregate`
+/// otherwise, mirroring how [`crate::operator::HashJoin`] and [`crate::operator::SortMergeJoin`]
+/// are chosen between for joins.
+///
+/// As with [`crate::rules::SelectJoinStrategyRule`], this is a single heuristic pick, not an
+/// enumeration of every physical alternative for a cascades-style cost-based search - `Rule::apply`
+/// only returns one `OptExpression`. The `SortAggregate` branch likewise needs a child whose
+/// `physical_props` carry an order: `PlanGraph` derives none, so such an order exists only where
+/// the input plan already carried one.
+#[derive(Clone, Debug)]
+pub struct SelectAggregateStrategyRule;
+
+impl Rule for SelectAggregateStrategyRule {
+    fn pattern(&self) -> Rc<Pattern> {
+        PATTERN.clone()
+    }
+
+    fn apply<O: Optimizer>(&self, input: OptExpression<O>, optimizer: &O) -> OptResult<Option<OptExpression<O>>> {
+        let schema = input.logical_prop().cloned();
+        let (node, inputs) = input.into_parts();
+        let aggregate = match &node {
+            OperatorNode(Logical(LogicalAggregate(aggregate))) => aggregate.clone(),
+            _ => return Ok(None),
+        };
+        let child = &inputs[0];
+
+        let child_handle = match child.node() {
+            ExprHandleNode(handle) => Some(*handle),
+            _ => None,
+        };
+
+        let group_cols = column_names(aggregate.group_by());
+        let prefers_sort = !group_cols.is_empty()
+            && child_handle
+                .and_then(|handle| optimizer.expr_at(handle).physical_props())
+                .and_then(|props| props.order())
+                .map_or(false, |order| order.satisfies(&OrderProp::new(group_cols)));
+
+        let physical_op = if prefers_sort {
+            Physical(PhysicalSortAggregate(SortAggregate::new(
+                aggregate.group_by().to_vec(),
+                aggregate.aggr_expr().to_vec(),
+            )))
+        } else {
+            Physical(PhysicalHashAggregate(HashAggregate::new(
+                aggregate.group_by().to_vec(),
+                aggregate.aggr_expr().to_vec(),
+            )))
+        };
+
+        Ok(Some(OptExpression::new(OperatorNode(physical_op), inputs).with_logical_prop(schema)))
+    }
+
+    fn name(&self) -> String {
+        "SelectAggregateStrategyRule".to_string()
+    }
+}
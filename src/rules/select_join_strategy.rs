@@ -0,0 +1,92 @@
+use std::rc::Rc;
+
+use crate::error::OptResult;
+use crate::operator::LogicalOperator::LogicalJoin;
+use crate::operator::Operator::{Logical, Physical};
+use crate::operator::PhysicalOperator::{PhysicalHashJoin, PhysicalNestedLoopJoin, PhysicalSortMergeJoin};
+use crate::operator::{equi_join_columns, split_equi_join_columns, HashJoin, NestedLoopJoin, SortMergeJoin};
+use crate::optimizer::{OptExpr, Optimizer};
+use crate::properties::{OrderProp, PhysicalProp};
+use crate::rules::OptExprNode::{ExprHandleNode, OperatorNode};
+use crate::rules::{any, pattern, OptExpression, PatterBuilder, Pattern, Rule};
+
+lazy_static! {
+    static ref PATTERN: Rc<Pattern> = Rc::new(
+        pattern(|op| matches!(op, Logical(LogicalJoin(_))))
+            .leaf(any)
+            .leaf(any)
+            .finish()
+    );
+}
+
+/// Picks a physical implementation for a `LogicalJoin`: `NestedLoopJoin` when the condition has no
+/// equi-join conjuncts to key a hash table or merge on, `SortMergeJoin` when both inputs already
+/// produce their join columns in order (so the sort is free), `HashJoin` otherwise - mirroring how
+/// [`crate::rules::SelectAggregateStrategyRule`] picks between `HashAggregate` and `SortAggregate`.
+///
+/// [`Rule::apply`] returns a single rewritten subtree rather than a set of alternatives, so this
+/// is a heuristic choice, not a cascades-style enumeration of every physical alternative for a
+/// cost-based search to pick from; that would need `Rule::apply` to hand back more than one
+/// `OptExpression`, which isn't part of its contract. The `SortMergeJoin` branch needs inputs whose
+/// `physical_props` carry an order: `PlanGraph` derives none, leaving `physical_props` as `None`
+/// on every node `insert_opt_node` adds and only copying them from the input plan in `from_plan`,
+/// so `already_sorted_on` finds an order only where the input plan already carried one.
+#[derive(Clone, Debug)]
+pub struct SelectJoinStrategyRule;
+
+impl Rule for SelectJoinStrategyRule {
+    fn pattern(&self) -> Rc<Pattern> {
+        PATTERN.clone()
+    }
+
+    fn apply<O: Optimizer>(&self, input: OptExpression<O>, optimizer: &O) -> OptResult<Option<OptExpression<O>>> {
+        let (node, inputs) = input.into_parts();
+        let join = match &node {
+            OperatorNode(Logical(LogicalJoin(join))) => join.clone(),
+            _ => return Ok(None),
+        };
+        let left = &inputs[0];
+        let right = &inputs[1];
+
+        let left_handle = match left.node() {
+            ExprHandleNode(handle) => Some(*handle),
+            _ => None,
+        };
+        let right_handle = match right.node() {
+            ExprHandleNode(handle) => Some(*handle),
+            _ => None,
+        };
+
+        let equi_keys = equi_join_columns(join.expr());
+        let (left_cols, right_cols) = left_handle
+            .zip(right_handle)
+            .and_then(|(l, r)| {
+                let left_schema = optimizer.expr_at(l).logical_prop()?.schema().clone();
+                let right_schema = optimizer.expr_at(r).logical_prop()?.schema().clone();
+                Some(split_equi_join_columns(&equi_keys, &left_schema, &right_schema))
+            })
+            .unwrap_or_default();
+
+        let already_sorted_on = |handle: Option<O::ExprHandle>, cols: &[String]| {
+            !cols.is_empty()
+                && handle
+                    .and_then(|h| optimizer.expr_at(h).physical_props())
+                    .and_then(|props| props.order())
+                    .map_or(false, |order| order.satisfies(&OrderProp::new(cols.to_vec())))
+        };
+
+        let physical_op = if equi_keys.is_empty() {
+            Physical(PhysicalNestedLoopJoin(NestedLoopJoin::new(join.join_type(), join.expr().clone())))
+        } else if already_sorted_on(left_handle, &left_cols) && already_sorted_on(right_handle, &right_cols) {
+            Physical(PhysicalSortMergeJoin(SortMergeJoin::new(join.join_type(), join.expr().clone())))
+        } else {
+            Physical(PhysicalHashJoin(HashJoin::new(join.join_type(), join.expr().clone())))
+        };
+
+        Ok(Some(OptExpression::new(OperatorNode(physical_op), inputs)))
+    }
+
+    fn name(&self) -> String {
+        "SelectJoinStrategyRule".to_string()
+    }
+}
@@ -0,0 +1,205 @@
+//! Statistics describing the data produced by a (sub)plan, used for cardinality estimation.
+
+use std::collections::HashMap;
+
+use crate::operator::LogicalOperator::{LogicalAggregate, LogicalJoin, LogicalLimit, LogicalProjection, LogicalScan};
+use crate::operator::Operator::{Logical, Physical};
+use crate::operator::PhysicalOperator::{PhysicalHashJoin, PhysicalNestedLoopJoin, PhysicalSortMergeJoin, PhysicalTableScan};
+use crate::operator::{column_names, equi_join_columns, Operator};
+use crate::optimizer::OptimizerContext;
+use crate::Expr;
+
+/// Fraction of the cartesian product of two inputs assumed to survive a join when no better
+/// estimate (e.g. column statistics on the join keys) is available. Matches the rule of thumb
+/// used by Calcite/most cost-based optimizers absent real histogram data.
+const DEFAULT_JOIN_SELECTIVITY: f64 = 0.1;
+
+/// Assumed average row width in bytes for statistics not given one via
+/// [`Statistics::with_row_width`]. Lets byte-size estimates - e.g. for deciding whether a join
+/// input is small enough to broadcast - be derived from a row count alone.
+const DEFAULT_ROW_WIDTH_BYTES: usize = 100;
+
+/// Per-column statistics, e.g. used to estimate join/filter selectivity.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ColumnStat {
+    distinct_count: Option<usize>,
+}
+
+impl ColumnStat {
+    pub fn new(distinct_count: Option<usize>) -> Self {
+        Self { distinct_count }
+    }
+
+    pub fn distinct_count(&self) -> Option<usize> {
+        self.distinct_count
+    }
+}
+
+/// Statistics of a relational expression's output.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Statistics {
+    row_count: usize,
+    row_width: usize,
+    column_stats: HashMap<String, ColumnStat>,
+}
+
+impl Statistics {
+    pub fn new(row_count: usize) -> Self {
+        Self {
+            row_count,
+            row_width: DEFAULT_ROW_WIDTH_BYTES,
+            column_stats: HashMap::new(),
+        }
+    }
+
+    pub fn row_count(&self) -> usize {
+        self.row_count
+    }
+
+    pub fn row_width(&self) -> usize {
+        self.row_width
+    }
+
+    pub fn with_row_width(mut self, row_width: usize) -> Self {
+        self.row_width = row_width;
+        self
+    }
+
+    /// Estimated output size in bytes, i.e. `row_count * row_width`. Used to decide, e.g. whether
+    /// a join input is small enough to broadcast rather than shuffle.
+    pub fn byte_size(&self) -> usize {
+        self.row_count.saturating_mul(self.row_width)
+    }
+
+    pub fn column_stat(&self, column: &str) -> Option<&ColumnStat> {
+        self.column_stats.get(column)
+    }
+
+    pub fn with_column_stat(mut self, column: impl Into<String>, stat: ColumnStat) -> Self {
+        self.column_stats.insert(column.into(), stat);
+        self
+    }
+}
+
+/// Derives an operator's output [`Statistics`] from its already-derived inputs', bottom-up.
+///
+/// Returns `None` when there isn't enough information to estimate anything, e.g. a `TableScan`
+/// with no `LIMIT` and no catalog stats for its table on `context`, or an input whose own
+/// statistics are unknown.
+pub fn derive_statistics(
+    operator: &Operator,
+    input_stats: &[Option<Statistics>],
+    context: &dyn OptimizerContext,
+) -> Option<Statistics> {
+    match operator {
+        Logical(LogicalScan(table_scan)) | Physical(PhysicalTableScan(table_scan)) => {
+            let catalog = context.table_stats(table_scan.table_name());
+            match (table_scan.limit(), catalog) {
+                // A LIMIT caps whatever the catalog says the table has, so the tighter of the two
+                // is the better estimate.
+                (Some(limit), Some(stats)) => Some(Statistics::new(limit.min(stats.row_count()))),
+                (Some(limit), None) => Some(Statistics::new(limit)),
+                (None, catalog) => catalog,
+            }
+        }
+        Logical(LogicalLimit(limit)) => {
+            let input = input_stats.first()?.as_ref()?;
+            Some(Statistics::new(input.row_count().min(limit.limit())))
+        }
+        Logical(LogicalProjection(_)) => input_stats.first().cloned().flatten(),
+        Logical(LogicalJoin(join)) => derive_join_statistics(join.expr(), input_stats),
+        Physical(PhysicalHashJoin(join)) => derive_join_statistics(join.expr(), input_stats),
+        Physical(PhysicalSortMergeJoin(join)) => derive_join_statistics(join.expr(), input_stats),
+        Physical(PhysicalNestedLoopJoin(join)) => derive_join_statistics(join.expr(), input_stats),
+        Logical(LogicalAggregate(aggregate)) => {
+            let input = input_stats.first()?.as_ref()?;
+            let row_count = column_names(aggregate.group_by())
+                .iter()
+                .map(|column| {
+                    input
+                        .column_stat(column)
+                        .and_then(ColumnStat::distinct_count)
+                        .unwrap_or(1) as f64
+                })
+                .product::<f64>()
+                .min(input.row_count() as f64);
+            Some(Statistics::new(row_count as usize))
+        }
+        _ => None,
+    }
+}
+
+/// Estimates a join's output `Statistics` from its equi-join keys' distinct counts: each equi-join
+/// key narrows the cartesian product by `1 / max(distinct_left, distinct_right)`, the same way
+/// Calcite/most cost-based optimizers estimate equi-join selectivity from column NDVs. Falls back
+/// to [`DEFAULT_JOIN_SELECTIVITY`] when no equi-join key has a known distinct count on either side
+/// (e.g. a non-equi join, or columns without catalog stats).
+fn derive_join_statistics(expr: &Expr, input_stats: &[Option<Statistics>]) -> Option<Statistics> {
+    let left = input_stats.first()?.as_ref()?;
+    let right = input_stats.get(1)?.as_ref()?;
+
+    let distinct_count = |column: &str| -> Option<usize> {
+        left.column_stat(column)
+            .or_else(|| right.column_stat(column))
+            .and_then(ColumnStat::distinct_count)
+    };
+
+    let selectivity = equi_join_columns(expr)
+        .into_iter()
+        .filter_map(|(a, b)| match (distinct_count(&a.name), distinct_count(&b.name)) {
+            (None, None) => None,
+            (da, db) => Some(1.0 / da.into_iter().chain(db).max().unwrap_or(1).max(1) as f64),
+        })
+        .fold(None, |acc: Option<f64>, key_selectivity| {
+            Some(acc.map_or(key_selectivity, |acc| acc * key_selectivity))
+        })
+        .unwrap_or(DEFAULT_JOIN_SELECTIVITY);
+
+    let row_count = (left.row_count() as f64) * (right.row_count() as f64) * selectivity;
+    Some(Statistics::new(row_count as usize))
+}
+
+#[cfg(test)]
+mod tests {
+    use datafusion::common::Column;
+    use datafusion::logical_plan::Operator as DFOperator;
+    use datafusion::prelude::JoinType;
+
+    use crate::operator::LogicalOperator::LogicalJoin;
+    use crate::operator::Operator::Logical;
+    use crate::operator::Join;
+    use crate::optimizer::DefaultOptimizerContext;
+    use crate::stat::{derive_statistics, ColumnStat, Statistics, DEFAULT_JOIN_SELECTIVITY};
+    use crate::Expr;
+
+    fn equi_join_expr(left: &str, right: &str) -> Expr {
+        Expr::BinaryExpr {
+            left: Box::new(Expr::Column(Column::from_name(left))),
+            op: DFOperator::Eq,
+            right: Box::new(Expr::Column(Column::from_name(right))),
+        }
+    }
+
+    #[test]
+    fn join_selectivity_uses_one_over_max_ndv() {
+        let left = Statistics::new(100).with_column_stat("a", ColumnStat::new(Some(10)));
+        let right = Statistics::new(50).with_column_stat("b", ColumnStat::new(Some(20)));
+
+        let join = Logical(LogicalJoin(Join::new(JoinType::Inner, equi_join_expr("a", "b"))));
+        let stats = derive_statistics(&join, &[Some(left), Some(right)], &DefaultOptimizerContext::default()).unwrap();
+
+        // selectivity = 1 / max(10, 20) = 0.05, so row_count = 100 * 50 * 0.05 = 250.
+        assert_eq!(stats.row_count(), 250);
+    }
+
+    #[test]
+    fn join_selectivity_falls_back_to_default_without_any_known_ndv() {
+        let left = Statistics::new(100);
+        let right = Statistics::new(50);
+
+        let join = Logical(LogicalJoin(Join::new(JoinType::Inner, equi_join_expr("a", "b"))));
+        let stats = derive_statistics(&join, &[Some(left), Some(right)], &DefaultOptimizerContext::default()).unwrap();
+
+        assert_eq!(stats.row_count(), (100.0 * 50.0 * DEFAULT_JOIN_SELECTIVITY) as usize);
+    }
+}